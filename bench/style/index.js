@@ -0,0 +1,56 @@
+const React = require('react')
+const ReactIs = require('react-is')
+const ReactWasmDOMServer = require('react-wasm-dom/server')
+// A build of the commit before styles were serialized in Rust, which still
+// calls JS' RegExp and `Array.prototype.includes` for every style.
+const ReactWasmDOMServerRegExp = require('react-wasm-dom-regexp/server')
+const Benchmark = require('benchmark')
+
+// A grid of cells with inline styles, which stresses the style serialization.
+function Cell({ index }) {
+  return React.createElement(
+    'div',
+    {
+      style: {
+        display: 'flex',
+        flexGrow: 1,
+        WebkitLineClamp: 2,
+        msFlexPositive: 1,
+        width: index * 1.5,
+        height: 0.1 + index / 3,
+        marginLeft: -index,
+        paddingTop: 1e-7 * index,
+        opacity: index / 100,
+        zIndex: index,
+        backgroundColor: index % 2 ? 'red' : 'blue',
+        transform: `translate3d(${index}px, 0, 0)`,
+        '--cell-index': index,
+      },
+    },
+    index
+  )
+}
+
+function Grid() {
+  const cells = []
+  for (let i = 0; i < 500; i++) {
+    cells.push(React.createElement(Cell, { key: i, index: i }))
+  }
+  return React.createElement('div', { style: { display: 'grid', gridColumnStart: 1 } }, cells)
+}
+
+const suite = new Benchmark.Suite()
+
+console.warn = () => {}
+console.error = () => {}
+
+const jsx = React.createElement(Grid)
+
+suite.add('Rust  #createMarkupForStyles', () => ReactWasmDOMServer.renderToString(React, ReactIs, jsx))
+suite.add('RegExp#createMarkupForStyles', () => ReactWasmDOMServerRegExp.renderToString(React, ReactIs, jsx))
+
+suite
+  .on('cycle', event => {
+    console.log(String(event.target));
+  })
+  .run({ async: true })
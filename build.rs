@@ -0,0 +1,91 @@
+//! Generates the perfect hash set of unitless style properties, including
+//! their vendor prefixed variants.
+
+use std::{
+    env,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Same as React's `isUnitlessNumber`.
+const UNITLESS_PROPERTIES: &[&str] = &[
+    "animationIterationCount",
+    "borderImageOutset",
+    "borderImageSlice",
+    "borderImageWidth",
+    "boxFlex",
+    "boxFlexGroup",
+    "boxOrdinalGroup",
+    "columnCount",
+    "columns",
+    "flex",
+    "flexGrow",
+    "flexPositive",
+    "flexShrink",
+    "flexNegative",
+    "flexOrder",
+    "gridArea",
+    "gridRow",
+    "gridRowEnd",
+    "gridRowSpan",
+    "gridRowStart",
+    "gridColumn",
+    "gridColumnEnd",
+    "gridColumnSpan",
+    "gridColumnStart",
+    "fontWeight",
+    "lineClamp",
+    "lineHeight",
+    "opacity",
+    "order",
+    "orphans",
+    "tabSize",
+    "widows",
+    "zIndex",
+    "zoom",
+    "fillOpacity",
+    "floodOpacity",
+    "stopOpacity",
+    "strokeDasharray",
+    "strokeDashoffset",
+    "strokeMiterlimit",
+    "strokeOpacity",
+    "strokeWidth",
+];
+
+const VENDOR_PREFIXES: &[&str] = &["Webkit", "ms", "Moz", "O"];
+
+/// Same as React's `prefixKey`, e.g. `WebkitLineClamp` for `lineClamp`.
+fn prefix_property(prefix: &str, property: &str) -> String {
+    let mut chars = property.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}{}", prefix, first.to_ascii_uppercase(), chars.as_str()),
+        None => prefix.to_string(),
+    }
+}
+
+fn main() {
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("unitless.rs");
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+
+    let properties = UNITLESS_PROPERTIES
+        .iter()
+        .map(|property| property.to_string())
+        .chain(VENDOR_PREFIXES.iter().flat_map(|prefix| {
+            UNITLESS_PROPERTIES
+                .iter()
+                .map(move |property| prefix_property(prefix, property))
+        }))
+        .collect::<Vec<_>>();
+    let mut set = phf_codegen::Set::new();
+    for property in &properties {
+        set.entry(property.as_str());
+    }
+    writeln!(
+        &mut file,
+        "static UNITLESS: phf::Set<&'static str> = {};",
+        set.build()
+    )
+    .unwrap();
+}
@@ -11,7 +11,6 @@
 
 const React = require('react');
 const ReactIs = require('react-is');
-const ReactDOM = require('react-dom');
 // const ReactDOMServer = require('react-dom/server');
 const ReactDOMServer = require('../../pkg/server');
 
@@ -59,6 +58,42 @@ describe('CSSPropertyOperations', () => {
     expect(html).toContain('"-ms-transition:none;-moz-transition:none"');
   });
 
+  it('should not append `px` to vendor-prefixed unitless styles', () => {
+    const styles = {
+      WebkitLineClamp: 3,
+      msFlexGrow: 1,
+      MozBoxFlex: 2,
+      OOrder: 1,
+      flexGrow: 1,
+      WebkitTransitionDuration: 0,
+      MozMarginStart: 4,
+    };
+    const div = <div style={styles} />;
+    const html = ReactDOMServer.renderToString(React, ReactIs, div);
+    expect(html).toContain(
+      '"-webkit-line-clamp:3;-ms-flex-grow:1;-moz-box-flex:2;-o-order:1;flex-grow:1;' +
+        '-webkit-transition-duration:0;-moz-margin-start:4px"',
+    );
+  });
+
+  it('should format numbers like JavaScript', () => {
+    const styles = {
+      width: 1.5,
+      height: 0.1 + 0.2,
+      marginLeft: -3,
+      paddingTop: 1e-7,
+      paddingBottom: 1e21,
+      flexGrow: 123456789012,
+      opacity: 0.000001,
+    };
+    const div = <div style={styles} />;
+    const html = ReactDOMServer.renderToString(React, ReactIs, div);
+    expect(html).toContain(
+      '"width:1.5px;height:0.30000000000000004px;margin-left:-3px;padding-top:1e-7px;' +
+        'padding-bottom:1e+21px;flex-grow:123456789012;opacity:0.000001"',
+    );
+  });
+
   it('should not hyphenate custom CSS property', () => {
     const styles = {
       '--someColor': '#000000',
@@ -88,25 +123,23 @@ describe('CSSPropertyOperations', () => {
     expect(/style=/.test(html)).toBe(false);
   });
 
-  xit('should warn when using hyphenated style names', () => {
+  it('should warn when using hyphenated style names', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
       render() {
-        return <div style={{'backgroundColor': 'crimson'}} />;
+        return <div style={{'background-color': 'crimson'}} />;
       }
     }
 
-    const root = document.createElement('div');
-
-    expect(() => ReactDOM.render(<Comp />, root)).toErrorDev(
-      'Warning: Unsupported style property background-color. Did you mean backgroundColor?' +
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Comp />)).toErrorDev(
+      'Unsupported style property background-color. Did you mean backgroundColor?' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     );
   });
 
-  xit('should warn when updating hyphenated style names', () => {
+  it('should warn when using hyphenated vendor-prefixed style names', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
@@ -116,23 +149,23 @@ describe('CSSPropertyOperations', () => {
     }
 
     const styles = {
-      'msTransform': 'translate3d(0, 0, 0)',
-      'webkitTransform': 'translate3d(0, 0, 0)',
+      '-ms-transform': 'translate3d(0, 0, 0)',
+      '-webkit-transform': 'translate3d(0, 0, 0)',
     };
-    const root = document.createElement('div');
-    ReactDOM.render(<Comp />, root);
 
-    expect(() => ReactDOM.render(<Comp style={styles} />, root)).toErrorDev([
-      'Warning: Unsupported style property -ms-transform. Did you mean msTransform?' +
+    expect(() =>
+      ReactDOMServer.renderToString(React, ReactIs, <Comp style={styles} />),
+    ).toErrorDev([
+      'Unsupported style property -ms-transform. Did you mean msTransform?' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
-      'Warning: Unsupported style property -webkit-transform. Did you mean WebkitTransform?' +
+      'Unsupported style property -webkit-transform. Did you mean WebkitTransform?' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     ]);
   });
 
-  xit('warns when miscapitalizing vendored style names', () => {
+  it('warns when miscapitalizing vendored style names', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
@@ -141,7 +174,7 @@ describe('CSSPropertyOperations', () => {
           <div
             style={{
               msTransform: 'translate3d(0, 0, 0)',
-              OTransform: 'translate3d(0, 0, 0)',
+              oTransform: 'translate3d(0, 0, 0)',
               webkitTransform: 'translate3d(0, 0, 0)',
             }}
           />
@@ -149,22 +182,20 @@ describe('CSSPropertyOperations', () => {
       }
     }
 
-    const root = document.createElement('div');
-
-    expect(() => ReactDOM.render(<Comp />, root)).toErrorDev([
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Comp />)).toErrorDev([
       // msTransform is correct already and shouldn't warn
-      'Warning: Unsupported vendor-prefixed style property oTransform. ' +
+      'Unsupported vendor-prefixed style property oTransform. ' +
         'Did you mean OTransform?' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
-      'Warning: Unsupported vendor-prefixed style property webkitTransform. ' +
+      'Unsupported vendor-prefixed style property webkitTransform. ' +
         'Did you mean WebkitTransform?' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     ]);
   });
 
-  xit('should warn about style having a trailing semicolon', () => {
+  it('should warn about style having a trailing semicolon', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
@@ -182,21 +213,19 @@ describe('CSSPropertyOperations', () => {
       }
     }
 
-    const root = document.createElement('div');
-
-    expect(() => ReactDOM.render(<Comp />, root)).toErrorDev([
-      "Warning: Style property values shouldn't contain a semicolon. " +
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Comp />)).toErrorDev([
+      "Style property values shouldn't contain a semicolon. " +
         'Try "backgroundColor: blue" instead.' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
-      "Warning: Style property values shouldn't contain a semicolon. " +
+      "Style property values shouldn't contain a semicolon. " +
         'Try "color: red" instead.' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     ]);
   });
 
-  xit('should warn about style containing a NaN value', () => {
+  it('should warn about style containing a NaN value', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
@@ -205,27 +234,24 @@ describe('CSSPropertyOperations', () => {
       }
     }
 
-    const root = document.createElement('div');
-
-    expect(() => ReactDOM.render(<Comp />, root)).toErrorDev(
-      'Warning: `NaN` is an invalid value for the `fontSize` css style property.' +
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Comp />)).toErrorDev(
+      '`NaN` is an invalid value for the `fontSize` css style property.' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     );
   });
 
-  xit('should not warn when setting CSS custom properties', () => {
+  it('should not warn when setting CSS custom properties', () => {
     class Comp extends React.Component {
       render() {
         return <div style={{'--foo-primary': 'red', backgroundColor: 'red'}} />;
       }
     }
 
-    const root = document.createElement('div');
-    ReactDOM.render(<Comp />, root);
+    ReactDOMServer.renderToString(React, ReactIs, <Comp />);
   });
 
-  xit('should warn about style containing a Infinity value', () => {
+  it('should warn about style containing a Infinity value', () => {
     class Comp extends React.Component {
       static displayName = 'Comp';
 
@@ -234,15 +260,23 @@ describe('CSSPropertyOperations', () => {
       }
     }
 
-    const root = document.createElement('div');
-
-    expect(() => ReactDOM.render(<Comp />, root)).toErrorDev(
-      'Warning: `Infinity` is an invalid value for the `fontSize` css style property.' +
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Comp />)).toErrorDev(
+      '`Infinity` is an invalid value for the `fontSize` css style property.' +
         '\n    in div (at **)' +
         '\n    in Comp (at **)',
     );
   });
 
+  it('should render booleans as empty and objects as strings', () => {
+    const styles = {
+      display: false,
+      color: {toString: () => ' red '},
+    };
+    const div = <div style={styles} />;
+    const html = ReactDOMServer.renderToString(React, ReactIs, div);
+    expect(html).toContain('"display:;color:red"');
+  });
+
   it('should not add units to CSS custom properties', () => {
     class Comp extends React.Component {
       render() {
@@ -254,4 +288,17 @@ describe('CSSPropertyOperations', () => {
 
     expect(html).toContain('--foo:5');
   });
+
+  it('should prefix a leading ms- of the hyphenated name like React', () => {
+    const styles = {MsTransition: 'none', 'ms-filter': 'none'};
+
+    let html;
+    expect(() => {
+      html = ReactDOMServer.renderToString(React, ReactIs, <div style={styles} />);
+    }).toErrorDev(
+      'Unsupported style property ms-filter. Did you mean msFilter?' +
+        '\n    in div (at **)',
+    );
+    expect(html).toContain('"-ms-transition:none;-ms-filter:none"');
+  });
 });
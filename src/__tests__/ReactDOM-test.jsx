@@ -85,4 +85,923 @@ describe('ReactDOMServer', () => {
     let res = ReactDOMServer.renderToString(React, ReactIs, <Test />);
     expect(res).toEqual('<div data-reactroot="">new</div>')
   });
-});
\ No newline at end of file
+
+  it('should separate adjacent text across fragments and components', () => {
+    function Text({children}) {
+      return children
+    }
+    const jsx = <div>a<>b{'c'}</><Text>d</Text><span>e</span>f</div>
+
+    let res = ReactDOMServer.renderToString(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div data-reactroot="">a<!-- -->b<!-- -->c<!-- -->d<span>e</span>f</div>'
+    )
+  });
+
+  it('should not separate text in static markup', () => {
+    const jsx = <div>a<>b</></div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<div>ab</div>')
+  });
+
+  it('should render any node at the root', () => {
+    expect(ReactDOMServer.renderToString(React, ReactIs, 'text')).toEqual('text')
+    expect(ReactDOMServer.renderToString(React, ReactIs, 42)).toEqual('42')
+    expect(ReactDOMServer.renderToString(React, ReactIs, null)).toEqual('')
+    expect(ReactDOMServer.renderToString(React, ReactIs, ['a', 'b'])).toEqual('a<!-- -->b')
+    expect(ReactDOMServer.renderToString(React, ReactIs, <><div /><span /></>)).toEqual(
+      '<div data-reactroot=""></div><span data-reactroot=""></span>'
+    )
+  });
+
+  it('should warn about functions at the root', () => {
+    expect(() => {
+      let res = ReactDOMServer.renderToString(React, ReactIs, () => {});
+      expect(res).toEqual('')
+    }).toErrorDev('Functions are not valid as a React child.', {withoutStack: true});
+  });
+
+  it('should rethrow errors of function components', () => {
+    const internals = React.__SECRET_INTERNALS_DO_NOT_USE_OR_YOU_WILL_BE_FIRED;
+    const dispatcher = internals.ReactCurrentDispatcher.current;
+    function Broken() {
+      throw new Error('Broken');
+    }
+
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <div><Broken /></div>)).toThrow('Broken')
+    expect(internals.ReactCurrentDispatcher.current).toBe(dispatcher)
+  });
+
+  it('should flatten nested arrays and iterables', () => {
+    function* generate() {
+      yield <i key="i" />;
+      yield 'c';
+    }
+    const jsx = <div>{[['a', [<b key="b" />]], new Set([generate()])]}</div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<div>a<b></b><i></i>c</div>')
+  });
+
+  it('should skip booleans and render BigInt children', () => {
+    const jsx = <div>{true}{false}{null}{undefined}{BigInt(10)}</div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<div>10</div>')
+  });
+
+  it('should throw for plain object children', () => {
+    const jsx = <div>{{a: 1, b: 2}}</div>
+
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, jsx)).toThrowError(
+      'Objects are not valid as a React child (found: object with keys {a, b}).'
+    )
+  });
+
+  it('should throw for void elements with children', () => {
+    function App() {
+      return <div><img>child</img></div>
+    }
+
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <App />)).toThrowError(
+      'img is a void element tag and must neither have `children` nor use ' +
+      '`dangerouslySetInnerHTML`.\n    in img (at **)\n    in div (at **)\n    in App (at **)'
+    )
+  });
+
+  it('should throw for invalid tag names', () => {
+    const Tag = 'div onclick="alert(1)"'
+
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, <Tag />)).toThrowError(
+      'Invalid tag: div onclick="alert(1)"'
+    )
+  });
+
+  it('should add the component stack to invalid child warnings', () => {
+    function App() {
+      return <div>{() => {}}{Symbol('foo')}</div>
+    }
+
+    expect(() => {
+      let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />);
+      expect(res).toEqual('<div></div>')
+    }).toErrorDev([
+      'Functions are not valid as a React child.',
+      'Symbols are not valid as a React child.\n  Symbol(foo)'
+    ]);
+  });
+
+  it('should drop unsafe attribute names', () => {
+    const props = {'"><script>alert(1)</script><div a': 'x', 'data-id': '1'}
+    const jsx = <div {...props} />
+
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    }).toErrorDev(
+      'Invalid attribute name: `"><script>alert(1)</script><div a`',
+      {withoutStack: true}
+    );
+    expect(res).toEqual('<div data-id="1"></div>')
+  });
+
+  it('should keep attributes that only start with on', () => {
+    const jsx = <my-element one="1" only="true" onboarding-step="2" onClickCapture={() => {}} />
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<my-element one="1" only="true" onboarding-step="2"></my-element>')
+  });
+
+  it('should warn about invalid event listeners', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(
+        React,
+        ReactIs,
+        <div onclick={() => {}} onMouseDown="alert(1)" />
+      );
+    }).toErrorDev([
+      'Invalid event handler property `onclick`. Did you mean `onClick`?',
+      'Expected `onMouseDown` listener to be a function, instead got a value of `string` type.',
+    ]);
+    expect(res).toEqual('<div></div>')
+  });
+
+  it('should ignore event handler attributes but keep other props starting with on', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(
+        React,
+        ReactIs,
+        <div one="1" only="2" onfocusin="x" ONSCROLLEND="x" onBoarding="x" />
+      );
+    }).toErrorDev([
+      'Unknown event handler property `onfocusin`. It will be ignored.',
+      'Unknown event handler property `ONSCROLLEND`. It will be ignored.',
+      'Unknown event handler property `onBoarding`. It will be ignored.',
+    ]);
+    expect(res).toEqual('<div one="1" only="2"></div>')
+  });
+
+  it('should not render reserved props', () => {
+    const jsx = (
+      <div key="a" suppressHydrationWarning suppressContentEditableWarning defaultValue="b">
+        <input defaultValue="a" defaultChecked />
+        <input value="c" defaultValue="d" readOnly />
+      </div>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div><input value="a" checked=""/><input value="c" readonly=""/></div>'
+    )
+  });
+
+  it('should render dangerouslySetInnerHTML unescaped', () => {
+    const jsx = <div dangerouslySetInnerHTML={{__html: '<b>bold</b>'}} />
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<div><b>bold</b></div>')
+  });
+
+  it('should throw for children and dangerouslySetInnerHTML', () => {
+    const jsx = <div dangerouslySetInnerHTML={{__html: 'a'}}>b</div>
+
+    expect(() => ReactDOMServer.renderToString(React, ReactIs, jsx)).toThrowError(
+      'Can only set one of `children` or `props.dangerouslySetInnerHTML`.'
+    )
+  });
+
+  it('should render boolean attributes', () => {
+    const jsx = <div hidden={false} disabled={true} download={true} draggable={false} />
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<div disabled="" download="" draggable="false"></div>')
+  });
+
+  it('should warn about invalid attribute values', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <div foo={() => {}} tabIndex={NaN} />);
+    }).toErrorDev([
+      'Invalid value for prop `foo` on <div> tag.',
+      'Received NaN for the `tabIndex` attribute.',
+    ]);
+    expect(res).toEqual('<div tabindex="NaN"></div>')
+  });
+
+  it('should render the value of a textarea as its content', () => {
+    const jsx = <div>
+      <textarea defaultValue="a <b>" />
+      <textarea value={'text'} onChange={() => {}} />
+      <textarea value={1} defaultValue="ignored" readOnly />
+    </div>
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div><textarea>a &lt;b&gt;</textarea><textarea>text</textarea>' +
+      '<textarea readonly="">1</textarea></div>'
+    )
+  });
+
+  it('should select the options of a select by its value', () => {
+    const jsx = <div>
+      <select defaultValue="b">
+        <option value="a">A</option>
+        <option value="b">B</option>
+        <optgroup label="More">
+          <option selected>c</option>
+        </optgroup>
+      </select>
+      <select value={[2, 'c']} multiple readOnly>
+        <option value={1}>A</option>
+        <option value={2}>B</option>
+        <option>{'c'}</option>
+      </select>
+    </div>
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div><select><option value="a">A</option><option value="b" selected="">B</option>' +
+      '<optgroup label="More"><option>c</option></optgroup></select>' +
+      '<select multiple=""><option value="1">A</option><option value="2" selected="">B</option>' +
+      '<option selected="">c</option></select></div>'
+    )
+  });
+
+  it('should render focusable as a booleanish string', () => {
+    const jsx = <svg focusable={false}><g focusable="true" /></svg>
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<svg focusable="false"><g focusable="true"></g></svg>')
+  });
+
+  it('should render svg elements and attributes', () => {
+    const jsx = (
+      <svg viewBox="0 0 10 10" xmlnsXlink="http://www.w3.org/1999/xlink">
+        <linearGradient id="g" gradientUnits="userSpaceOnUse" />
+        <use xlinkHref="#g" xmlLang="en" strokeWidth={2} fillOpacity="0.5" />
+        <foreignObject>
+          <div className="a" tabIndex={1} />
+        </foreignObject>
+      </svg>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<svg viewBox="0 0 10 10" xmlns:xlink="http://www.w3.org/1999/xlink">' +
+      '<linearGradient id="g" gradientUnits="userSpaceOnUse"></linearGradient>' +
+      '<use xlink:href="#g" xml:lang="en" stroke-width="2" fill-opacity="0.5"></use>' +
+      '<foreignObject><div class="a" tabindex="1"></div></foreignObject></svg>'
+    )
+  });
+
+  it('should warn about incorrect casing of html elements', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(
+        React,
+        ReactIs,
+        <svg><foreignObject>{React.createElement('Div')}</foreignObject></svg>
+      );
+    }).toErrorDev(
+      '<Div /> is using incorrect casing. Use PascalCase for React components, ' +
+      'or lowercase for HTML elements.'
+    );
+    expect(res).toEqual('<svg><foreignObject><Div></Div></foreignObject></svg>')
+  });
+
+  it('should only start the svg namespace with a lowercase svg tag', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(
+        React,
+        ReactIs,
+        React.createElement('SVG', null, <foreignObject />)
+      );
+    }).toErrorDev([
+      '<SVG /> is using incorrect casing. Use PascalCase for React components, ' +
+      'or lowercase for HTML elements.',
+      '<foreignObject /> is using incorrect casing. Use PascalCase for React components, ' +
+      'or lowercase for HTML elements.',
+    ]);
+    expect(res).toEqual('<SVG><foreignObject></foreignObject></SVG>')
+  });
+
+  it('should render props of custom elements as attributes', () => {
+    const jsx = (
+      <div>
+        <my-element className="a" open={true} hidden={false} config={{a: 1}} count={2} tabIndex="1" />
+        <div is="fancy-div" className="b" htmlFor="c" />
+      </div>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div><my-element class="a" open="" count="2" tabIndex="1"></my-element>' +
+      '<div is="fancy-div" class="b" htmlFor="c"></div></div>'
+    )
+  });
+
+  it('should not escape the content of script and style', () => {
+    const json = '{"a":"</script><script>alert(1)</script>"}'
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToString(
+        React,
+        ReactIs,
+        <div>
+          <script type="application/json">{json}</script>
+          <style>{'a > b { content: "</STYLE>"; }'}</style>
+        </div>
+      );
+    }).toErrorDev([
+      'The content of <script> is not escaped like text.',
+      'The content of <style> is not escaped like text.',
+    ]);
+    expect(res).toEqual(
+      '<div data-reactroot=""><script type="application/json">' +
+      '{"a":"</\\u0073cript><\\u0073cript>alert(1)</\\u0073cript>"}</script>' +
+      '<style>a > b { content: "</\\53 TYLE>"; }</style></div>'
+    )
+  });
+
+  it('should render dangerouslySetInnerHTML of script as is', () => {
+    const jsx = <script dangerouslySetInnerHTML={{__html: 'if (a < b && c) {}'}} />
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<script>if (a < b && c) {}</script>')
+  });
+
+  it('should not separate text in escapable raw text elements', () => {
+    const name = 'World'
+    const jsx = <title>Hello {name} & {'<you>'}</title>
+
+    let res = ReactDOMServer.renderToString(React, ReactIs, jsx);
+    expect(res).toEqual('<title data-reactroot="">Hello World &amp; &lt;you&gt;</title>')
+  });
+
+  it('should preserve a leading newline in pre, textarea and listing', () => {
+    const jsx = (
+      <div>
+        <pre>{'\nfn main() {}'}</pre>
+        <textarea dangerouslySetInnerHTML={{__html: '\ntext'}} />
+        <textarea defaultValue={'\nvalue'} />
+        <listing>{'\n'}{'a'}</listing>
+        <pre>{'no newline'}</pre>
+      </div>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div><pre>\n\nfn main() {}</pre><textarea>\n\ntext</textarea><textarea>\n\nvalue</textarea>' +
+      '<listing>\n\na</listing><pre>no newline</pre></div>'
+    )
+  });
+
+  it('should render a whole document', () => {
+    const jsx = (
+      <html lang="en">
+        <head>
+          <title>App</title>
+        </head>
+        <body>
+          <div id="root" />
+        </body>
+      </html>
+    )
+
+    let res = ReactDOMServer.renderToString(React, ReactIs, jsx, {
+      bootstrapScripts: ['/main.js'],
+      bootstrapModules: ['/module.js'],
+      nonce: 'R4nd0m',
+    });
+    expect(res).toEqual(
+      '<!DOCTYPE html><html lang="en"><head><title>App</title></head><body><div id="root"></div>' +
+      '<script src="/main.js" nonce="R4nd0m" async=""></script>' +
+      '<script type="module" src="/module.js" nonce="R4nd0m" async=""></script></body></html>'
+    )
+  });
+
+  it('should add bootstrap scripts after markup without body', () => {
+    const jsx = <div>App</div>
+
+    let res = ReactDOMServer.renderToString(React, ReactIs, jsx, {bootstrapScripts: ['/main.js']});
+    expect(res).toEqual('<div data-reactroot="">App</div><script src="/main.js" async=""></script>')
+  });
+
+  it('should warn about nested document elements', () => {
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <div><body /></div>);
+    }).toErrorDev('validateDOMNesting(...): <body> cannot appear as a child of <div>.');
+    expect(res).toEqual('<div><body></body></div>')
+  });
+
+  it('should add bootstrap scripts at the end of a document without body', () => {
+    const jsx = <html><head><title>App</title></head><div id="root" /></html>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {bootstrapScripts: ['/main.js']});
+    expect(res).toEqual(
+      '<!DOCTYPE html><html><head><title>App</title></head><div id="root"></div>' +
+      '<script src="/main.js" async=""></script></html>'
+    )
+  });
+
+  it('should hoist head elements into a separate result', () => {
+    function Page() {
+      return (
+        <article>
+          <title>Page</title>
+          <meta name="description" content="Page description" />
+          <link rel="stylesheet" href="/page.css" precedence="high" />
+          <link rel="stylesheet" href="/base.css" precedence="low" />
+          <meta itemProp="name" content="Article" />
+          <svg><title>Icon</title></svg>
+          Text
+        </article>
+      )
+    }
+    const jsx = (
+      <div>
+        <title>App</title>
+        <meta charSet="utf-8" />
+        <meta name="description" content="App description" />
+        <link rel="stylesheet" href="/base.css" precedence="low" />
+        <link rel="preload" href="/font.woff2" as="font" />
+        <link rel="preload" href="/font.woff2" as="font" />
+        <Page />
+      </div>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, jsx);
+    expect(res.html).toEqual(
+      '<div><article><meta itemProp="name" content="Article"/>' +
+      '<svg><title>Icon</title></svg>Text</article></div>'
+    )
+    expect(res.head).toEqual(
+      '<meta charSet="utf-8"/><title>Page</title>' +
+      '<meta name="description" content="Page description"/>' +
+      '<link rel="preload" href="/font.woff2" as="font"/>' +
+      '<link rel="stylesheet" href="/base.css" data-precedence="low"/>' +
+      '<link rel="stylesheet" href="/page.css" data-precedence="high"/>'
+    )
+  });
+
+  it('should hoist head elements into the head of a document', () => {
+    const jsx = (
+      <html>
+        <head />
+        <body>
+          <title>App</title>
+        </body>
+      </html>
+    )
+
+    let res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, jsx);
+    expect(res.html).toEqual(
+      '<!DOCTYPE html><html><head><title>App</title></head><body></body></html>'
+    )
+    expect(res.head).toEqual('')
+  });
+
+  it('should write the hoisted charset and viewport first', () => {
+    function App() {
+      return (
+        <html>
+          <head><title>App</title></head>
+          <body>
+            <meta name="viewport" content="width=device-width" />
+            <meta charSet="utf-8" />
+          </body>
+        </html>
+      );
+    }
+
+    const res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, <App />);
+    expect(res.html).toEqual(
+      '<!DOCTYPE html><html><head><meta charSet="utf-8"/>' +
+      '<meta name="viewport" content="width=device-width"/>' +
+      '<title>App</title></head><body></body></html>'
+    )
+  });
+
+  it('should flush resources ahead of the markup', () => {
+    function Fonts() {
+      ReactDOMServer.preconnect('https://fonts.example.com', {crossOrigin: 'anonymous'});
+      ReactDOMServer.prefetchDNS('https://cdn.example.com');
+      ReactDOMServer.preload('/font.woff2', {as: 'font', type: 'font/woff2', crossOrigin: 'anonymous'});
+      ReactDOMServer.preload('/font.woff2', {as: 'font', type: 'font/woff2', crossOrigin: 'anonymous'});
+      return <span>Text</span>;
+    }
+    function App() {
+      ReactDOMServer.preload('/app.js', {as: 'script'});
+      ReactDOMServer.preinit('/app.js', {as: 'script'});
+      ReactDOMServer.preinit('/app.css', {as: 'style', precedence: 'high'});
+      return <div><Fonts /></div>;
+    }
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />);
+    expect(res).toEqual(
+      '<link rel="preconnect" href="https://fonts.example.com" crossorigin=""/>' +
+      '<link rel="dns-prefetch" href="https://cdn.example.com"/>' +
+      '<link rel="preload" href="/font.woff2" as="font" crossorigin="" type="font/woff2"/>' +
+      '<link rel="stylesheet" href="/app.css" data-precedence="high"/>' +
+      '<script src="/app.js" async=""></script>' +
+      '<div><span>Text</span></div>'
+    )
+
+    ReactDOMServer.preload('/outside.js', {as: 'script'});
+    res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <div />);
+    expect(res).toEqual('<div></div>')
+  });
+
+  it('should leave out preloads of preinitialized resources', () => {
+    function App() {
+      ReactDOMServer.preload('/app.css', {as: 'style'});
+      ReactDOMServer.preload('/app.js', {as: 'script'});
+      ReactDOMServer.preinit('/app.css', {as: 'style'});
+      return <div />;
+    }
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />);
+    expect(res).toEqual(
+      '<link rel="preload" href="/app.js" as="script"/>' +
+      '<link rel="stylesheet" href="/app.css" data-precedence="default"/>' +
+      '<div></div>'
+    )
+  });
+
+  it('should add a head to a document without one for its resources', () => {
+    function App() {
+      ReactDOMServer.preinit('/app.css', {as: 'style'});
+      return <html><body>Text</body></html>;
+    }
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />);
+    expect(res).toEqual(
+      '<!DOCTYPE html><html><head>' +
+      '<link rel="stylesheet" href="/app.css" data-precedence="default"/></head>' +
+      '<body>Text</body></html>'
+    )
+  });
+
+  it('should write resources after the hoisted charset and viewport', () => {
+    function App() {
+      ReactDOMServer.preinit('/app.css', {as: 'style'});
+      return (
+        <html>
+          <head><title>App</title></head>
+          <body>
+            <meta name="viewport" content="width=device-width" />
+            <meta charSet="utf-8" />
+          </body>
+        </html>
+      );
+    }
+
+    const res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, <App />);
+    expect(res.html).toEqual(
+      '<!DOCTYPE html><html><head><meta charSet="utf-8"/>' +
+      '<meta name="viewport" content="width=device-width"/>' +
+      '<link rel="stylesheet" href="/app.css" data-precedence="default"/>' +
+      '<title>App</title></head><body></body></html>'
+    )
+  });
+
+  it('should extract styles into classes', () => {
+    let jsx = <div style={{color: 'red'}}>
+      <span className="label" style={{color: 'red', fontSize: 12}}>A</span>
+      <span style={{color: 'red'}}>B</span>
+      <span style={{}}>C</span>
+    </div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {extractStyles: true, nonce: 'abc'});
+    expect(res).toEqual(
+      '<style nonce="abc">.sh66u19{color:red}.s38wp58{color:red;font-size:12px}</style>' +
+      '<div class="sh66u19"><span class="label s38wp58">A</span>' +
+      '<span class="sh66u19">B</span><span>C</span></div>'
+    )
+
+    res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, jsx, {extractStyles: true});
+    expect(res.styles).toEqual('.sh66u19{color:red}.s38wp58{color:red;font-size:12px}')
+    expect(res.html).toEqual(
+      '<div class="sh66u19"><span class="label s38wp58">A</span>' +
+      '<span class="sh66u19">B</span><span>C</span></div>'
+    )
+
+    res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<div style="color:red"><span class="label" style="color:red;font-size:12px">A</span>' +
+      '<span style="color:red">B</span><span>C</span></div>'
+    )
+  });
+
+  it('should give extracted styles with the same hash different classes', () => {
+    // Both styles have the same 32 bit FNV-1a hash.
+    const jsx = <div>
+      <span style={{height: 63319}} />
+      <span style={{height: 138200}} />
+      <span style={{height: 63319}} />
+    </div>
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {extractStyles: true});
+    expect(res).toEqual(
+      '<style>.sda0g32{height:63319px}.sda0g32-1{height:138200px}</style>' +
+      '<div><span class="sda0g32"></span><span class="sda0g32-1"></span>' +
+      '<span class="sda0g32"></span></div>'
+    )
+  });
+
+  it('should keep styles inline that could break out of their rule', () => {
+    const jsx = <div>
+      <span style={{color: 'red}body{display:none'}} />
+      <span style={{color: 'red;display:none'}} />
+      <span style={{color: '</style><script>alert(1)</script>'}} />
+      <span style={{content: '";"'}} />
+      <span style={{color: 'blue'}} />
+    </div>
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {extractStyles: true});
+    expect(res).toEqual(
+      '<style>.s1n9mrr{content:";"}.s7mx2v2{color:blue}</style>' +
+      '<div><span style="color:red}body{display:none"></span>' +
+      '<span style="color:red;display:none"></span>' +
+      '<span style="color:&lt;/style&gt;&lt;script&gt;alert(1)&lt;/script&gt;"></span>' +
+      '<span class="s1n9mrr"></span><span class="s7mx2v2"></span></div>'
+    )
+  });
+
+  it('should only write the rules of the stylesheet that apply to the markup', () => {
+    let stylesheet = `
+      /* Layout */
+      @import url("fonts.css");
+      body, .page { margin: 0 }
+      .sidebar { width: 200px }
+      nav > a.active:hover, button { color: red }
+      #main { padding: 8px }
+      @media (max-width: 600px) {
+        .sidebar { display: none }
+        .page { padding: 0 }
+      }
+      @font-face { font-family: Inter; src: url("inter.woff2") }
+      [hidden], * { box-sizing: border-box }
+    `
+    let jsx = <div className="page">
+      <nav><a className="active" href="/">Home</a></nav>
+      <main id="main" />
+    </div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {stylesheet});
+    expect(res).toEqual(
+      '<style>@import url("fonts.css");.page{margin: 0}nav > a.active:hover{color: red}' +
+      '#main{padding: 8px}@media (max-width: 600px){.page{padding: 0}}' +
+      '@font-face{font-family: Inter; src: url("inter.woff2")}[hidden],*{box-sizing: border-box}</style>' +
+      '<div class="page"><nav><a class="active" href="/">Home</a></nav><main id="main"></main></div>'
+    )
+
+    res = ReactDOMServer.renderToStaticMarkupWithHead(React, ReactIs, <span id="main" />, {stylesheet});
+    expect(res.styles).toEqual(
+      '@import url("fonts.css");#main{padding: 8px}@font-face{font-family: Inter; src: url("inter.woff2")}' +
+      '[hidden],*{box-sizing: border-box}'
+    )
+    expect(res.html).toEqual('<span id="main"></span>')
+  });
+
+  it('should keep the stylesheet rules for markup from dangerouslySetInnerHTML', () => {
+    const stylesheet = `
+      .card { padding: 8px }
+      .note em { color: gray }
+      #intro { margin: 0 }
+      table { border: 0 }
+    `
+    const html = '<p id=intro class="note lead">A <EM>B</EM></p><!-- <table> -->'
+
+    const res = ReactDOMServer.renderToStaticMarkup(
+      React,
+      ReactIs,
+      <div className="card" dangerouslySetInnerHTML={{__html: html}} />,
+      {stylesheet}
+    );
+    expect(res).toEqual(
+      '<style>.card{padding: 8px}.note em{color: gray}#intro{margin: 0}</style>' +
+      '<div class="card">' + html + '</div>'
+    )
+  });
+
+  it('should add a head to a document without one for its styles', () => {
+    function App() {
+      ReactDOMServer.preinit('/app.css', {as: 'style'});
+      return <html><body>Text</body></html>;
+    }
+
+    const res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />, {stylesheet: 'body { margin: 0 }'});
+    expect(res).toEqual(
+      '<!DOCTYPE html><html><head><style>body{margin: 0}</style>' +
+      '<link rel="stylesheet" href="/app.css" data-precedence="default"/></head>' +
+      '<body>Text</body></html>'
+    )
+  });
+
+  it('should pretty print block-level elements', () => {
+    let jsx = <html>
+      <head><title>Mail</title></head>
+      <body>
+        <div className="card">
+          <h1>Hello <b>World</b></h1>
+          <pre>{'  keep\n  this'}</pre>
+          Some <em>inline</em> text
+          <img src="https://example.com/a-rather-long-image-url.png" alt="A rather long description" />
+        </div>
+      </body>
+    </html>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {pretty: true});
+    expect(res).toEqual(
+      '<!DOCTYPE html>\n' +
+      '<html>\n' +
+      '  <head>\n' +
+      '    <title>Mail</title>\n' +
+      '  </head>\n' +
+      '  <body>\n' +
+      '    <div class="card">\n' +
+      '      <h1>Hello <b>World</b></h1>\n' +
+      '      <pre>  keep\n  this</pre>\n' +
+      '      Some <em>inline</em> text<img src="https://example.com/a-rather-long-image-url.png" alt="A rather long description"/>\n' +
+      '    </div>\n' +
+      '  </body>\n' +
+      '</html>'
+    )
+
+    res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <div>
+      <section id="main" className="a-rather-long-class-name" data-testid="section"><p>A</p></section>
+    </div>, {pretty: true, printWidth: 40});
+    expect(res).toEqual(
+      '<div>\n' +
+      '  <section\n' +
+      '    id="main"\n' +
+      '    class="a-rather-long-class-name"\n' +
+      '    data-testid="section"\n' +
+      '  >\n' +
+      '    <p>A</p>\n' +
+      '  </section>\n' +
+      '</div>'
+    )
+  });
+
+  it('should sort attributes by name if requested', () => {
+    let first = {id: 'link', href: '/'};
+    let second = {title: 'Home', 'data-b': 'b', 'aria-label': 'Home', 'data-a': 'a'};
+    let a = <a {...first} {...second}>Home</a>;
+    let b = <a {...second} {...first}>Home</a>;
+
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, a)).toEqual(
+      '<a id="link" href="/" title="Home" data-b="b" aria-label="Home" data-a="a">Home</a>'
+    )
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, b)).toEqual(
+      '<a title="Home" data-b="b" aria-label="Home" data-a="a" id="link" href="/">Home</a>'
+    )
+    let sorted = '<a aria-label="Home" data-a="a" data-b="b" href="/" id="link" title="Home">Home</a>';
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, a, {sortAttributes: true})).toEqual(sorted)
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, b, {sortAttributes: true})).toEqual(sorted)
+  });
+
+  it('should drop integer-like attribute names without reordering', () => {
+    let element = <div title="b" {...{'2': 'two', '1': 'one'}} id="a" />;
+
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, element)).toEqual(
+      '<div title="b" id="a"></div>'
+    )
+  });
+
+  it('should render well-formed XML', () => {
+    let jsx = <div>
+      <input type="checkbox" checked={true} readOnly={true} />
+      <pre>{'\nCode & more'}</pre>
+      {'Text'}{' with \u0001control'}
+      <script>{'if (a < b) {}'}</script>
+      <svg viewBox="0 0 10 10"><use xlinkHref="#icon" /></svg>
+    </div>
+
+    let res = ReactDOMServer.renderToString(React, ReactIs, jsx, {xml: true});
+    expect(res).toEqual(
+      '<div xmlns="http://www.w3.org/1999/xhtml">' +
+      '<input type="checkbox" checked="checked" readonly="readonly"/>' +
+      '<pre>\nCode &amp; more</pre>Text with control' +
+      '<script>if (a &lt; b) {}</script>' +
+      '<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">' +
+      '<use xlink:href="#icon" xmlns:xlink="http://www.w3.org/1999/xlink"></use></svg></div>'
+    )
+  });
+
+  it('should write preinitialized scripts as async once', () => {
+    function App() {
+      ReactDOMServer.preinit('/app.js', {as: 'script', nonce: 'R4nd0m'});
+      return <div />;
+    }
+
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />)).toEqual(
+      '<script src="/app.js" async="" nonce="R4nd0m"></script><div></div>'
+    )
+    expect(ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />, {xml: true})).toEqual(
+      '<script xmlns="http://www.w3.org/1999/xhtml" src="/app.js" async="async" nonce="R4nd0m"></script>' +
+      '<div xmlns="http://www.w3.org/1999/xhtml"></div>'
+    )
+  });
+
+  it('should inline class styles for email', () => {
+    let stylesheet = `
+      .button { color: white; background: blue; }
+      .button, td.cell { padding: 8px }
+      .shadow { box-shadow: 0 0 2px black }
+      @media (max-width: 600px) { .button { width: 100% } }
+    `
+    let jsx = <table>
+      <tbody>
+        <tr>
+          <td className="cell"><a className="button shadow" href="/" style={{color: 'red'}}>Go</a></td>
+          <td style={{position: 'relative'}}>Text</td>
+        </tr>
+      </tbody>
+    </table>
+
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToEmailMarkup(React, ReactIs, jsx, {stylesheet});
+    }).toErrorDev([
+      'The CSS property `position` is not supported by all major email clients.' +
+        '\n    in td (at **)' +
+        '\n    in tr (at **)' +
+        '\n    in tbody (at **)' +
+        '\n    in table (at **)',
+      'The CSS property `box-shadow` is not supported by all major email clients.',
+    ], {withoutStack: 1});
+    expect(res).toEqual(
+      '<style>td.cell{padding: 8px}@media (max-width: 600px){.button{width: 100%}}</style>' +
+      '<table border="0" cellpadding="0" cellspacing="0" role="presentation"><tbody><tr><td class="cell">' +
+      '<a class="button shadow" href="/" style="color: white; background: blue;padding: 8px;' +
+      'box-shadow: 0 0 2px black;color:red">Go</a></td>' +
+      '<td style="position:relative">Text</td></tr></tbody></table>'
+    )
+  });
+
+  it('should not split email styles within strings and urls', () => {
+    const jsx = <div style={{background: 'url("a;transform:none.png")', filter: 'none'}} />
+
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToEmailMarkup(React, ReactIs, jsx);
+    }).toErrorDev(
+      'The CSS property `filter` is not supported by all major email clients.' +
+        '\n    in div (at **)'
+    );
+    expect(res).toEqual('<div style="background:url(&quot;a;transform:none.png&quot;);filter:none"></div>')
+  });
+
+  it('should reset the spacing of tables for email', () => {
+    const jsx = <table cellPadding={4} role="grid"><tbody><tr><td>
+      <table><tbody><tr><td>Text</td></tr></tbody></table>
+    </td></tr></tbody></table>
+
+    const res = ReactDOMServer.renderToEmailMarkup(React, ReactIs, jsx);
+    expect(res).toEqual(
+      '<table cellPadding="4" role="grid" border="0" cellspacing="0"><tbody><tr><td>' +
+      '<table border="0" cellpadding="0" cellspacing="0" role="presentation">' +
+      '<tbody><tr><td>Text</td></tr></tbody></table></td></tr></tbody></table>'
+    )
+  });
+
+  it('should write ASCII only if requested', () => {
+    let jsx = <div title="naïve">
+      <p>{'café ☕ 😀 \uD800'}</p>
+      <script>{'var s = "é\uD83D";'}</script>
+      <style>{'a::after { content: "→" }'}</style>
+      <div dangerouslySetInnerHTML={{__html: '<b>ü</b>'}} />
+    </div>
+
+    let res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {ascii: true});
+    expect(res).toEqual(
+      '<div title="na&#xef;ve"><p>caf&#xe9; &#x2615; &#x1f600; &#xfffd;</p>' +
+      '<script>var s = "\\u00e9\\ud83d";</script>' +
+      '<style>a::after { content: "\\2192 " }</style>' +
+      '<div><b>&#xfc;</b></div></div>'
+    )
+  });
+
+  it('should leave out attributes with non-ASCII names in ASCII output', () => {
+    const jsx = <my-element data-größe="1" data-size="ö" />
+
+    let res;
+    expect(() => {
+      res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx, {ascii: true});
+    }).toErrorDev(
+      'The attribute `data-größe` on <my-element> is left out, because its name can\'t be ' +
+        'written as ASCII.\n    in my-element (at **)'
+    );
+    expect(res).toEqual('<my-element data-size="&#xf6;"></my-element>')
+
+    res = ReactDOMServer.renderToStaticMarkup(React, ReactIs, jsx);
+    expect(res).toEqual('<my-element data-größe="1" data-size="ö"></my-element>')
+  });
+});
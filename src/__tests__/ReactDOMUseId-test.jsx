@@ -0,0 +1,84 @@
+/**
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @emails react-core
+ * @jest-environment node
+ */
+
+'use strict';
+
+let React;
+let ReactIs;
+let ReactDOMServer;
+
+// React 16 does not export `useId`, so we call the dispatcher directly.
+function useId() {
+  return React.__SECRET_INTERNALS_DO_NOT_USE_OR_YOU_WILL_BE_FIRED.ReactCurrentDispatcher.current.useId();
+}
+
+describe('useId', () => {
+  beforeEach(() => {
+    jest.resetModules();
+    React = require('react');
+    ReactIs = require('react-is');
+    // ReactDOMServer = require('react-dom/server');
+    ReactDOMServer = require('../../pkg/server');
+  });
+
+  function Child() {
+    const id = useId();
+    return <div id={id} />;
+  }
+
+  it('generates ids from the position in the tree', () => {
+    function App() {
+      return (
+        <div>
+          <Child />
+          <Child />
+        </div>
+      );
+    }
+
+    const html = ReactDOMServer.renderToString(React, ReactIs, <App />);
+    expect(html).toEqual(
+      '<div data-reactroot=""><div id=":R1:"></div><div id=":R2:"></div></div>',
+    );
+  });
+
+  it('generates unique ids for multiple calls in one component', () => {
+    function App() {
+      const id1 = useId();
+      const id2 = useId();
+      return (
+        <div>
+          <label title={id1} id={id2} />
+          <Child />
+        </div>
+      );
+    }
+
+    const html = ReactDOMServer.renderToStaticMarkup(React, ReactIs, <App />);
+    expect(html).toEqual(
+      '<div><label title=":R0:" id=":R0H1:"></label><div id=":R5:"></div></div>',
+    );
+  });
+
+  it('prefixes ids with identifierPrefix', () => {
+    const html = ReactDOMServer.renderToStaticMarkup(
+      React,
+      ReactIs,
+      <div>
+        <Child />
+        <Child />
+      </div>,
+      {identifierPrefix: 'custom-'},
+    );
+    expect(html).toEqual(
+      '<div><div id=":custom-R1:"></div><div id=":custom-R2:"></div></div>',
+    );
+  });
+});
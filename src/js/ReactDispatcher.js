@@ -0,0 +1,141 @@
+/**
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * This is a modified version of ReactFizzHooks.
+ */
+
+function noop() {}
+
+function getCurrentDispatcher(React) {
+  return React.__SECRET_INTERNALS_DO_NOT_USE_OR_YOU_WILL_BE_FIRED
+    .ReactCurrentDispatcher;
+}
+
+/**
+ * Creates the hooks dispatcher for a single server render.
+ * The tree id of the component that is currently rendering is set by the
+ * renderer via `prepareToUseHooks`.
+ *
+ * @param {?string} identifierPrefix Prefix for all ids created by `useId`.
+ */
+const ReactDispatcher = identifierPrefix => {
+  const idPrefix = identifierPrefix === undefined ? '' : identifierPrefix;
+  let treeId = '';
+  let localIdCounter = 0;
+  let previousDispatcher = null;
+
+  function readContext(context) {
+    return context._currentValue;
+  }
+
+  function useState(initialState) {
+    return useReducer(null, initialState);
+  }
+
+  function useReducer(reducer, initialArg, init) {
+    let initialState;
+    if (init !== undefined) {
+      initialState = init(initialArg);
+    } else if (reducer === null && typeof initialArg === 'function') {
+      initialState = initialArg();
+    } else {
+      initialState = initialArg;
+    }
+    return [initialState, noop];
+  }
+
+  function useMemo(nextCreate) {
+    return nextCreate();
+  }
+
+  function useCallback(callback) {
+    return callback;
+  }
+
+  function useRef(initialValue) {
+    return {current: initialValue};
+  }
+
+  function useTransition() {
+    return [false, callback => callback()];
+  }
+
+  function useDeferredValue(value) {
+    return value;
+  }
+
+  function useSyncExternalStore(subscribe, getSnapshot, getServerSnapshot) {
+    if (getServerSnapshot === undefined) {
+      throw new Error(
+        'Missing getServerSnapshot, which is required for ' +
+          'server-rendered content. Will revert to client rendering.',
+      );
+    }
+    return getServerSnapshot();
+  }
+
+  /**
+   * Creates an id from the position of the current component in the tree.
+   * Matches the ids generated by `react-dom/server`, so that they survive
+   * hydration.
+   */
+  function useId() {
+    const localId = localIdCounter++;
+    let id = ':' + idPrefix + 'R' + treeId;
+    if (localId > 0) {
+      id += 'H' + localId.toString(32);
+    }
+    return id + ':';
+  }
+
+  const dispatcher = {
+    readContext,
+    useContext: readContext,
+    useState,
+    useReducer,
+    useMemo,
+    useCallback,
+    useRef,
+    useEffect: noop,
+    useLayoutEffect: noop,
+    useInsertionEffect: noop,
+    useImperativeHandle: noop,
+    useDebugValue: noop,
+    useTransition,
+    useDeferredValue,
+    useSyncExternalStore,
+    useId,
+  };
+
+  return {
+    install(React) {
+      const currentDispatcher = getCurrentDispatcher(React);
+      previousDispatcher = currentDispatcher.current;
+      currentDispatcher.current = dispatcher;
+    },
+
+    uninstall(React) {
+      getCurrentDispatcher(React).current = previousDispatcher;
+      previousDispatcher = null;
+    },
+
+    prepareToUseHooks(id) {
+      treeId = id;
+      localIdCounter = 0;
+    },
+
+    /**
+     * @return {boolean} True if the component called `useId`.
+     */
+    finishHooks() {
+      return localIdCounter !== 0;
+    },
+  };
+};
+
+module.exports = {
+  ReactDispatcher
+}
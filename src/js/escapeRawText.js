@@ -0,0 +1,23 @@
+// Taken from ReactFizzConfigDOM. The content of `<script>` and `<style>` is
+// not escaped like text, so only the sequences that could close the element
+// early are rewritten in a way that keeps the script or stylesheet valid.
+const scriptRegex = /(<\/|<)(s)(cript)/gi;
+const scriptReplacer = (match, prefix, s, suffix) =>
+  `${prefix}${s === 's' ? '\\u0073' : '\\u0053'}${suffix}`;
+
+const styleRegex = /(<\/|<)(s)(tyle)/gi;
+const styleReplacer = (match, prefix, s, suffix) =>
+  `${prefix}${s === 's' ? '\\73 ' : '\\53 '}${suffix}`;
+
+function escapeScriptContent(content) {
+  return ('' + content).replace(scriptRegex, scriptReplacer);
+}
+
+function escapeStyleContent(content) {
+  return ('' + content).replace(styleRegex, styleReplacer);
+}
+
+module.exports = {
+  escapeScriptContent,
+  escapeStyleContent
+}
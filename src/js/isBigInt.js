@@ -0,0 +1,8 @@
+// wasm-bindgen does not expose `typeof` checks for BigInt values.
+function isBigInt(value) {
+  return typeof value === 'bigint';
+}
+
+module.exports = {
+  isBigInt
+}
@@ -0,0 +1,291 @@
+/// React's synthetic events with the name of the DOM event they listen to.
+const EVENTS: &[(&str, &str)] = &[
+    ("onAbort", "abort"),
+    ("onAnimationEnd", "animationend"),
+    ("onAnimationIteration", "animationiteration"),
+    ("onAnimationStart", "animationstart"),
+    ("onAuxClick", "auxclick"),
+    ("onBeforeInput", "beforeinput"),
+    ("onBlur", "blur"),
+    ("onCanPlay", "canplay"),
+    ("onCanPlayThrough", "canplaythrough"),
+    ("onCancel", "cancel"),
+    ("onChange", "change"),
+    ("onClick", "click"),
+    ("onClose", "close"),
+    ("onCompositionEnd", "compositionend"),
+    ("onCompositionStart", "compositionstart"),
+    ("onCompositionUpdate", "compositionupdate"),
+    ("onContextMenu", "contextmenu"),
+    ("onCopy", "copy"),
+    ("onCut", "cut"),
+    ("onDoubleClick", "dblclick"),
+    ("onDrag", "drag"),
+    ("onDragEnd", "dragend"),
+    ("onDragEnter", "dragenter"),
+    ("onDragExit", "dragexit"),
+    ("onDragLeave", "dragleave"),
+    ("onDragOver", "dragover"),
+    ("onDragStart", "dragstart"),
+    ("onDrop", "drop"),
+    ("onDurationChange", "durationchange"),
+    ("onEmptied", "emptied"),
+    ("onEncrypted", "encrypted"),
+    ("onEnded", "ended"),
+    ("onError", "error"),
+    ("onFocus", "focus"),
+    ("onGotPointerCapture", "gotpointercapture"),
+    ("onInput", "input"),
+    ("onInvalid", "invalid"),
+    ("onKeyDown", "keydown"),
+    ("onKeyPress", "keypress"),
+    ("onKeyUp", "keyup"),
+    ("onLoad", "load"),
+    ("onLoadStart", "loadstart"),
+    ("onLoadedData", "loadeddata"),
+    ("onLoadedMetadata", "loadedmetadata"),
+    ("onLostPointerCapture", "lostpointercapture"),
+    ("onMouseDown", "mousedown"),
+    ("onMouseEnter", "mouseenter"),
+    ("onMouseLeave", "mouseleave"),
+    ("onMouseMove", "mousemove"),
+    ("onMouseOut", "mouseout"),
+    ("onMouseOver", "mouseover"),
+    ("onMouseUp", "mouseup"),
+    ("onPaste", "paste"),
+    ("onPause", "pause"),
+    ("onPlay", "play"),
+    ("onPlaying", "playing"),
+    ("onPointerCancel", "pointercancel"),
+    ("onPointerDown", "pointerdown"),
+    ("onPointerEnter", "pointerenter"),
+    ("onPointerLeave", "pointerleave"),
+    ("onPointerMove", "pointermove"),
+    ("onPointerOut", "pointerout"),
+    ("onPointerOver", "pointerover"),
+    ("onPointerUp", "pointerup"),
+    ("onProgress", "progress"),
+    ("onRateChange", "ratechange"),
+    ("onReset", "reset"),
+    ("onScroll", "scroll"),
+    ("onSeeked", "seeked"),
+    ("onSeeking", "seeking"),
+    ("onSelect", "select"),
+    ("onStalled", "stalled"),
+    ("onSubmit", "submit"),
+    ("onSuspend", "suspend"),
+    ("onTimeUpdate", "timeupdate"),
+    ("onToggle", "toggle"),
+    ("onTouchCancel", "touchcancel"),
+    ("onTouchEnd", "touchend"),
+    ("onTouchMove", "touchmove"),
+    ("onTouchStart", "touchstart"),
+    ("onTransitionEnd", "transitionend"),
+    ("onVolumeChange", "volumechange"),
+    ("onWaiting", "waiting"),
+    ("onWheel", "wheel"),
+];
+
+/// These events don't bubble, so React doesn't offer a capture phase.
+const WITHOUT_CAPTURE: &[&str] = &[
+    "onMouseEnter",
+    "onMouseLeave",
+    "onPointerEnter",
+    "onPointerLeave",
+];
+
+/// Event handler attributes that browsers run as script. Sorted, so it can
+/// be searched with `binary_search`.
+const EVENT_HANDLER_ATTRIBUTES: &[&str] = &[
+    "onabort",
+    "onafterprint",
+    "onanimationcancel",
+    "onanimationend",
+    "onanimationiteration",
+    "onanimationstart",
+    "onauxclick",
+    "onbeforeinput",
+    "onbeforematch",
+    "onbeforeprint",
+    "onbeforetoggle",
+    "onbeforeunload",
+    "onblur",
+    "oncancel",
+    "oncanplay",
+    "oncanplaythrough",
+    "onchange",
+    "onclick",
+    "onclose",
+    "oncontextlost",
+    "oncontextmenu",
+    "oncontextrestored",
+    "oncopy",
+    "oncuechange",
+    "oncut",
+    "ondblclick",
+    "ondrag",
+    "ondragend",
+    "ondragenter",
+    "ondragexit",
+    "ondragleave",
+    "ondragover",
+    "ondragstart",
+    "ondrop",
+    "ondurationchange",
+    "onemptied",
+    "onended",
+    "onerror",
+    "onfocus",
+    "onfocusin",
+    "onfocusout",
+    "onformdata",
+    "onfullscreenchange",
+    "onfullscreenerror",
+    "ongotpointercapture",
+    "onhashchange",
+    "oninput",
+    "oninvalid",
+    "onkeydown",
+    "onkeypress",
+    "onkeyup",
+    "onlanguagechange",
+    "onload",
+    "onloadeddata",
+    "onloadedmetadata",
+    "onloadstart",
+    "onlostpointercapture",
+    "onmessage",
+    "onmessageerror",
+    "onmousedown",
+    "onmouseenter",
+    "onmouseleave",
+    "onmousemove",
+    "onmouseout",
+    "onmouseover",
+    "onmouseup",
+    "onmousewheel",
+    "onoffline",
+    "ononline",
+    "onpagehide",
+    "onpagereveal",
+    "onpageshow",
+    "onpageswap",
+    "onpaste",
+    "onpause",
+    "onplay",
+    "onplaying",
+    "onpointercancel",
+    "onpointerdown",
+    "onpointerenter",
+    "onpointerleave",
+    "onpointermove",
+    "onpointerout",
+    "onpointerover",
+    "onpointerrawupdate",
+    "onpointerup",
+    "onpopstate",
+    "onprogress",
+    "onratechange",
+    "onrejectionhandled",
+    "onreset",
+    "onresize",
+    "onscroll",
+    "onscrollend",
+    "onsearch",
+    "onsecuritypolicyviolation",
+    "onseeked",
+    "onseeking",
+    "onselect",
+    "onselectionchange",
+    "onselectstart",
+    "onslotchange",
+    "onstalled",
+    "onstorage",
+    "onsubmit",
+    "onsuspend",
+    "ontimeupdate",
+    "ontoggle",
+    "ontouchcancel",
+    "ontouchend",
+    "ontouchmove",
+    "ontouchstart",
+    "ontransitioncancel",
+    "ontransitionend",
+    "ontransitionrun",
+    "ontransitionstart",
+    "onunhandledrejection",
+    "onunload",
+    "onvolumechange",
+    "onwaiting",
+    "onwebkitanimationend",
+    "onwebkitanimationiteration",
+    "onwebkitanimationstart",
+    "onwebkittransitionend",
+    "onwheel",
+];
+
+const CAPTURE: &str = "Capture";
+
+pub struct EventRegistration {
+    pub dom_event_name: &'static str,
+    pub capture: bool,
+}
+
+/// Looks up a prop name like `onClick` or `onClickCapture`.
+/// Returns `None` if the prop is not an event listener.
+pub fn get_registration(prop_name: &str) -> Option<EventRegistration> {
+    if let Some(dom_event_name) = find_event(prop_name) {
+        return Some(EventRegistration {
+            dom_event_name,
+            capture: false,
+        });
+    }
+    if let Some(prop_name) = prop_name.strip_suffix(CAPTURE) {
+        if !WITHOUT_CAPTURE.contains(&prop_name) {
+            if let Some(dom_event_name) = find_event(prop_name) {
+                return Some(EventRegistration {
+                    dom_event_name,
+                    capture: true,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Finds the registered name of a misspelled event listener, e.g.
+/// `onClick` for `onclick`.
+pub fn get_possible_registration_name(prop_name: &str) -> Option<String> {
+    EVENTS.iter().find_map(|(registration_name, _)| {
+        if prop_name.eq_ignore_ascii_case(registration_name) {
+            Some(registration_name.to_string())
+        } else if !WITHOUT_CAPTURE.contains(registration_name)
+            && prop_name.len() == registration_name.len() + CAPTURE.len()
+            && prop_name.is_char_boundary(registration_name.len())
+            && prop_name[..registration_name.len()].eq_ignore_ascii_case(registration_name)
+            && prop_name[registration_name.len()..].eq_ignore_ascii_case(CAPTURE)
+        {
+            Some(format!("{}{}", registration_name, CAPTURE))
+        } else {
+            None
+        }
+    })
+}
+
+fn find_event(registration_name: &str) -> Option<&'static str> {
+    EVENTS
+        .iter()
+        .find(|(name, _)| *name == registration_name)
+        .map(|(_, dom_event_name)| *dom_event_name)
+}
+
+/// Whether a prop name is meant to be an event handler: `on` followed by an
+/// uppercase letter like React's listeners, or an event handler attribute of
+/// the browser in any case, like `onfocusin`.
+pub fn is_event_handler_name(prop_name: &str) -> bool {
+    let bytes = prop_name.as_bytes();
+    (bytes.len() > 2 && bytes.starts_with(b"on") && bytes[2].is_ascii_uppercase())
+        || EVENT_HANDLER_ATTRIBUTES
+            .binary_search(&prop_name.to_ascii_lowercase().as_str())
+            .is_ok()
+}
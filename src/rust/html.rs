@@ -1,58 +1,160 @@
-use crate::{constants::*, react::escape_html};
+use crate::{
+    constants::*,
+    react::{escape_html, escape_script_content, escape_style_content},
+};
 
-use js_sys::{JsString, Reflect};
-use wasm_bindgen::JsCast;
+use js_sys::JsString;
+
+/// Elements that are written inline when pretty printing, because
+/// whitespace around them would show up as space in the text.
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "del", "dfn", "em",
+    "i", "img", "input", "ins", "kbd", "label", "mark", "meter", "output", "picture", "progress",
+    "q", "ruby", "s", "samp", "select", "small", "span", "strong", "sub", "sup", "svg", "time",
+    "u", "var", "video", "wbr",
+];
+
+/// How the markup is written.
+#[derive(Clone, Copy, Default)]
+pub struct Format {
+    /// Indents block-level elements, if set.
+    pub pretty: Option<Pretty>,
+    /// Writes attributes sorted by name instead of in the order of the
+    /// props, so that the output doesn't depend on how props are spread.
+    pub sort_attributes: bool,
+    /// Writes well-formed XML, with boolean attributes written as
+    /// `name="name"` and characters that XML doesn't allow left out.
+    pub xml: bool,
+    /// Writes every character outside of ASCII as an escape sequence.
+    pub ascii: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct Pretty {
+    /// Number of spaces per level.
+    pub indent: usize,
+    /// Start tags that would get longer than this have every attribute on a
+    /// line of its own.
+    pub width: usize,
+}
+
+impl Default for Pretty {
+    fn default() -> Self {
+        Pretty {
+            indent: 2,
+            width: 80,
+        }
+    }
+}
+
+impl Format {
+    fn compact(self) -> Format {
+        Format {
+            pretty: None,
+            ..self
+        }
+    }
+}
+
+/// Attributes in the order they have been set. Setting an attribute again
+/// replaces its value in place, same as for the properties of an object.
+/// Unlike for objects, names that look like integers don't move to the front,
+/// but those are no valid attribute names and never get set. Boolean
+/// attributes have no value, because HTML and XML write them differently.
+#[derive(Default)]
+pub struct Attributes(Vec<(JsString, Option<JsString>)>);
+
+impl Attributes {
+    pub fn new() -> Self {
+        Attributes(Vec::new())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&JsString> {
+        self.0
+            .iter()
+            .find(|(other, _)| *other == name)
+            .and_then(|(_, value)| value.as_ref())
+    }
+
+    pub fn set(&mut self, name: JsString, value: JsString) {
+        self.set_value(name, Some(value));
+    }
+
+    pub fn set_boolean(&mut self, name: JsString) {
+        self.set_value(name, None);
+    }
+
+    /// Sets an attribute and moves it in front of all others.
+    pub fn set_first(&mut self, name: JsString, value: JsString) {
+        self.remove(&String::from(name.clone()));
+        self.0.insert(0, (name, Some(value)));
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.0.retain(|(other, _)| *other != name);
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&JsString) -> bool) {
+        self.0.retain(|(name, _)| f(name));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(JsString, Option<JsString>)> {
+        self.0.iter()
+    }
+
+    /// The attributes sorted by name.
+    pub fn sorted(&self) -> Vec<&(JsString, Option<JsString>)> {
+        let mut attributes = self.0.iter().collect::<Vec<_>>();
+        attributes.sort_by_cached_key(|(name, _)| String::from(name.clone()));
+        attributes
+    }
+
+    fn set_value(&mut self, name: JsString, value: Option<JsString>) {
+        match self.0.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, other)) => *other = value,
+            None => self.0.push((name, value)),
+        }
+    }
+}
 
 pub struct HTMLElement {
     pub tag: JsString,
-    pub attributes: js_sys::Object,
+    pub attributes: Attributes,
     pub children: Vec<HTMLValue>,
 }
 
 impl HTMLElement {
-    pub fn render(&self) -> JsString {
-        EMPTY.with(|empty| {
-            OPEN_TAG.with(|open_tag| {
-                CLOSE_TAG.with(|close_tag| {
-                    SELF_OPEN_TAG.with(|self_open_tag| {
-                        SELF_CLOSE_TAG.with(|self_close_tag| {
-                            SPACE.with(|space| {
-                                ATTR_START.with(|attr_start| {
-                                    ATTR_END.with(|attr_end| {
-                                        let mut res = open_tag.concat(&self.tag);
-                                        js_sys::Object::keys(&self.attributes)
-                                            .iter()
-                                            .map(|key| {
-                                                let value =
-                                                    Reflect::get(&self.attributes, &key).unwrap();
-                                                [key, value]
-                                            })
-                                            .for_each(|[attr_key, attr_value]| {
-                                                res = res
-                                                    .concat(&space)
-                                                    .concat(&attr_key)
-                                                    .concat(&attr_start)
-                                                    .concat(&escape_html(
-                                                        &attr_value.unchecked_into(),
-                                                    ))
-                                                    .concat(&attr_end);
-                                            });
-                                        if self.is_self_closing() {
-                                            res.concat(&self_close_tag)
-                                        } else {
-                                            let children = self
-                                                .children
-                                                .iter()
-                                                .map(|child| child.render())
-                                                .collect::<js_sys::Array>()
-                                                .join(empty);
-                                            res.concat(&close_tag)
-                                                .concat(&children)
-                                                .concat(&self_open_tag)
-                                                .concat(&self.tag)
-                                                .concat(&close_tag)
-                                        }
-                                    })
+    pub fn render(&self, format: &Format) -> JsString {
+        if let Some(pretty) = format.pretty {
+            let mut html = String::new();
+            self.write_pretty(&mut html, format, pretty, 0);
+            return html.into();
+        }
+        OPEN_TAG.with(|open_tag| {
+            CLOSE_TAG.with(|close_tag| {
+                SELF_OPEN_TAG.with(|self_open_tag| {
+                    SELF_CLOSE_TAG.with(|self_close_tag| {
+                        SPACE.with(|space| {
+                            ATTR_START.with(|attr_start| {
+                                ATTR_END.with(|attr_end| {
+                                    let mut res = open_tag.concat(&self.tag);
+                                    self.for_each_attribute(format, |name, value| {
+                                        res = res
+                                            .concat(space)
+                                            .concat(name)
+                                            .concat(attr_start)
+                                            .concat(&value)
+                                            .concat(attr_end);
+                                    });
+                                    if self.is_self_closing() {
+                                        res.concat(self_close_tag)
+                                    } else {
+                                        res.concat(close_tag)
+                                            .concat(&self.render_children(format))
+                                            .concat(self_open_tag)
+                                            .concat(&self.tag)
+                                            .concat(close_tag)
+                                    }
                                 })
                             })
                         })
@@ -62,23 +164,391 @@ impl HTMLElement {
         })
     }
 
-    fn is_self_closing(&self) -> bool {
+    pub fn is_self_closing(&self) -> bool {
         SELF_CLOSING.with(|self_closing| self_closing.includes(&self.tag, 0))
     }
+
+    fn eats_leading_newline(&self) -> bool {
+        NEWLINE_EATING.with(|newline_eating| newline_eating.includes(&self.tag, 0))
+    }
+
+    /// Calls `f` with the name and the escaped value of every attribute.
+    fn for_each_attribute(&self, format: &Format, mut f: impl FnMut(&JsString, JsString)) {
+        let write = |(name, value): &(JsString, Option<JsString>)| match value {
+            Some(value) => f(name, escape(value, format)),
+            None if format.xml => f(name, name.clone()),
+            None => EMPTY.with(|empty| f(name, empty.clone())),
+        };
+        if format.sort_attributes {
+            self.attributes.sorted().into_iter().for_each(write);
+        } else {
+            self.attributes.iter().for_each(write);
+        }
+    }
+
+    fn render_children(&self, format: &Format) -> JsString {
+        let content_model = if format.xml {
+            ContentModel::Normal
+        } else {
+            ContentModel::of_element(&String::from(self.tag.clone()))
+        };
+        // Character references don't work in scripts and styles, so their
+        // content is escaped as a whole, with the escapes of JS and CSS.
+        let children = match content_model {
+            ContentModel::Script | ContentModel::Style if format.ascii => {
+                let format = Format {
+                    ascii: false,
+                    ..*format
+                };
+                to_ascii(&render_values(&self.children, &format), content_model)
+            }
+            _ => render_values(&self.children, format),
+        };
+        // Compensate for the newline that the browser is going to drop. XML
+        // parsers keep it.
+        if !format.xml && self.eats_leading_newline() && children.starts_with("\n", 0) {
+            NEWLINE.with(|newline| newline.concat(&children))
+        } else {
+            children
+        }
+    }
+
+    /// Block-level children are written on lines of their own. Inline
+    /// content and the content of `pre`, `textarea`, `script` and `style` is
+    /// written as is, because whitespace in there is significant.
+    fn write_pretty(&self, html: &mut String, format: &Format, pretty: Pretty, depth: usize) {
+        let indent = " ".repeat(pretty.indent * depth);
+        html.push_str(&indent);
+        self.write_start_tag(html, format, &indent, pretty);
+        if self.is_self_closing() {
+            return;
+        }
+        let tag = String::from(self.tag.clone());
+        let keeps_whitespace = self.eats_leading_newline()
+            || ContentModel::of_element(&tag) != ContentModel::Normal
+            || self.children.iter().all(HTMLValue::is_inline);
+        if keeps_whitespace {
+            html.push_str(&String::from(self.render_children(&format.compact())));
+        } else {
+            write_pretty_values(html, &self.children, format, pretty, depth + 1);
+            html.push('\n');
+            html.push_str(&indent);
+        }
+        html.push_str("</");
+        html.push_str(&tag);
+        html.push('>');
+    }
+
+    fn write_start_tag(&self, html: &mut String, format: &Format, indent: &str, pretty: Pretty) {
+        let tag = String::from(self.tag.clone());
+        let mut attributes = Vec::new();
+        self.for_each_attribute(format, |name, value| {
+            attributes.push(format!(
+                "{}=\"{}\"",
+                String::from(name.clone()),
+                String::from(value)
+            ))
+        });
+        let end = if self.is_self_closing() { "/>" } else { ">" };
+        let length = indent.len()
+            + 1
+            + tag.chars().count()
+            + attributes
+                .iter()
+                .map(|attribute| 1 + attribute.chars().count())
+                .sum::<usize>()
+            + end.len();
+        html.push('<');
+        html.push_str(&tag);
+        if length <= pretty.width {
+            for attribute in &attributes {
+                html.push(' ');
+                html.push_str(attribute);
+            }
+        } else {
+            let attribute_indent = " ".repeat(pretty.indent);
+            for attribute in &attributes {
+                html.push('\n');
+                html.push_str(indent);
+                html.push_str(&attribute_indent);
+                html.push_str(attribute);
+            }
+            html.push('\n');
+            html.push_str(indent);
+        }
+        html.push_str(end);
+    }
 }
 
 pub enum HTMLValue {
     Element(HTMLElement),
     Text(JsString),
+    /// Markup from `dangerouslySetInnerHTML`, which is written as is.
+    Raw(JsString),
     Comment,
 }
 
 impl HTMLValue {
-    pub fn render(&self) -> JsString {
+    pub fn render(&self, format: &Format) -> JsString {
         match self {
-            Self::Element(element) => element.render(),
-            Self::Text(text) => escape_html(text),
+            Self::Element(element) => element.render(format),
+            Self::Text(text) => escape(text, format),
+            Self::Raw(html) if format.ascii => to_ascii(html, ContentModel::Normal),
+            Self::Raw(html) => html.clone(),
             Self::Comment => COMMENT.with(|comment| comment.clone()),
         }
     }
+
+    fn is_inline(&self) -> bool {
+        match self {
+            Self::Element(element) => {
+                INLINE_ELEMENTS.contains(&String::from(element.tag.clone()).as_str())
+            }
+            _ => true,
+        }
+    }
+}
+
+fn escape(text: &JsString, format: &Format) -> JsString {
+    let escaped = if format.xml {
+        escape_xml(text)
+    } else {
+        escape_html(text)
+    };
+    if format.ascii {
+        to_ascii(&escaped, ContentModel::Normal)
+    } else {
+        escaped
+    }
+}
+
+pub fn is_ascii(text: &JsString) -> bool {
+    text.iter().all(|unit| unit < 0x80)
+}
+
+/// Writes every character outside of ASCII as character reference, or as
+/// escape sequence in scripts and styles. Scripts keep lone surrogates as
+/// `\u` escapes, which JS strings can hold, while everywhere else they are
+/// written as U+FFFD, which is what the browser would read for them anyway.
+fn to_ascii(text: &JsString, content_model: ContentModel) -> JsString {
+    if is_ascii(text) {
+        return text.clone();
+    }
+    let units = text.iter().collect::<Vec<u16>>();
+    let mut ascii = String::with_capacity(units.len() * 2);
+    if content_model == ContentModel::Script {
+        for unit in units {
+            if unit < 0x80 {
+                ascii.push(unit as u8 as char);
+            } else {
+                ascii.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    } else {
+        for c in std::char::decode_utf16(units) {
+            let c = c.unwrap_or(std::char::REPLACEMENT_CHARACTER);
+            if c.is_ascii() {
+                ascii.push(c);
+            } else if content_model == ContentModel::Style {
+                ascii.push_str(&format!("\\{:x} ", u32::from(c)));
+            } else {
+                ascii.push_str(&format!("&#x{:x};", u32::from(c)));
+            }
+        }
+    }
+    ascii.as_str().into()
+}
+
+/// Same as `escape_html`, but leaves out the characters that XML doesn't
+/// allow at all, not even as character reference.
+fn escape_xml(text: &JsString) -> JsString {
+    let text = String::from(text);
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped.as_str().into()
+}
+
+/// How the browser parses the text inside of an element.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ContentModel {
+    Normal,
+    /// Raw text of a `script`, which ends at the first `</script`.
+    Script,
+    /// Raw text of a `style`, which ends at the first `</style`.
+    Style,
+    /// Text of a `textarea` or `title`, which can contain character
+    /// references but no markup.
+    EscapableRawText,
+}
+
+impl ContentModel {
+    pub fn of_element(tag: &str) -> ContentModel {
+        match tag {
+            "script" => ContentModel::Script,
+            "style" => ContentModel::Style,
+            "textarea" | "title" => ContentModel::EscapableRawText,
+            _ => ContentModel::Normal,
+        }
+    }
+}
+
+/// Receives the output of the renderer and builds the tree of `HTMLValue`s.
+/// Adjacent text nodes are separated by an empty comment, no matter across
+/// how many component or fragment boundaries they have been rendered, so that
+/// they don't get merged into a single text node on hydration.
+pub struct HTMLWriter {
+    /// Open elements with their content model and whether the value before
+    /// them was text.
+    stack: Vec<(HTMLElement, ContentModel, bool)>,
+    root: Vec<HTMLValue>,
+    separate_text: bool,
+    /// XML parsers don't know raw text elements, so all text is escaped.
+    xml: bool,
+    previous_was_text: bool,
+}
+
+impl HTMLWriter {
+    pub fn new(separate_text: bool, xml: bool) -> Self {
+        HTMLWriter {
+            stack: Vec::new(),
+            root: Vec::new(),
+            separate_text,
+            xml,
+            previous_was_text: false,
+        }
+    }
+
+    pub fn open_element(&mut self, element: HTMLElement) {
+        let content_model = if self.xml {
+            ContentModel::Normal
+        } else {
+            ContentModel::of_element(&String::from(element.tag.clone()))
+        };
+        self.stack
+            .push((element, content_model, self.previous_was_text));
+        self.previous_was_text = false;
+    }
+
+    pub fn close_element(&mut self) {
+        self.previous_was_text = false;
+        if let Some((element, _, _)) = self.stack.pop() {
+            self.push(HTMLValue::Element(element));
+        }
+    }
+
+    /// Closes the current element without adding it to its parent, so that
+    /// it can be written somewhere else.
+    pub fn take_element(&mut self) -> Option<HTMLElement> {
+        self.stack.pop().map(|(element, _, previous_was_text)| {
+            self.previous_was_text = previous_was_text;
+            element
+        })
+    }
+
+    pub fn is_inside(&self, tag: &str) -> bool {
+        self.stack.iter().any(|(element, _, _)| element.tag == tag)
+    }
+
+    /// Text is escaped depending on the element it is written into. Inside
+    /// of raw text elements, comments would become part of the text, so
+    /// adjacent text is never separated there.
+    pub fn write_text(&mut self, text: JsString) {
+        if text.length() == 0 {
+            return;
+        }
+        match self.content_model() {
+            ContentModel::Normal => {
+                if self.separate_text && self.previous_was_text {
+                    self.push(HTMLValue::Comment);
+                }
+                self.push(HTMLValue::Text(text));
+                self.previous_was_text = true;
+            }
+            ContentModel::Script => self.push(HTMLValue::Raw(escape_script_content(&text))),
+            ContentModel::Style => self.push(HTMLValue::Raw(escape_style_content(&text))),
+            ContentModel::EscapableRawText => self.push(HTMLValue::Text(text)),
+        }
+    }
+
+    pub fn write_raw(&mut self, html: JsString) {
+        self.previous_was_text = false;
+        self.push(HTMLValue::Raw(html));
+    }
+
+    pub fn finish(self) -> Vec<HTMLValue> {
+        self.root
+    }
+
+    pub fn parent_tag(&self) -> Option<String> {
+        self.stack
+            .last()
+            .map(|(element, _, _)| String::from(element.tag.clone()))
+    }
+
+    fn content_model(&self) -> ContentModel {
+        self.stack
+            .last()
+            .map_or(ContentModel::Normal, |(_, content_model, _)| *content_model)
+    }
+
+    fn push(&mut self, value: HTMLValue) {
+        match self.stack.last_mut() {
+            Some((parent, _, _)) => parent.children.push(value),
+            None => self.root.push(value),
+        }
+    }
+}
+
+pub fn render_values(values: &[HTMLValue], format: &Format) -> JsString {
+    if let Some(pretty) = format.pretty {
+        let mut html = String::new();
+        write_pretty_values(&mut html, values, format, pretty, 0);
+        return html.trim_start_matches('\n').into();
+    }
+    EMPTY.with(|empty| {
+        values
+            .iter()
+            .map(|value| value.render(format))
+            .collect::<js_sys::Array>()
+            .join(empty)
+    })
+}
+
+/// Writes every block-level element and every run of inline content on a
+/// line of its own.
+fn write_pretty_values(
+    html: &mut String,
+    values: &[HTMLValue],
+    format: &Format,
+    pretty: Pretty,
+    depth: usize,
+) {
+    let indent = " ".repeat(pretty.indent * depth);
+    let mut values = values.iter().peekable();
+    while let Some(value) = values.next() {
+        html.push('\n');
+        match value {
+            HTMLValue::Element(element) if !value.is_inline() => {
+                element.write_pretty(html, format, pretty, depth)
+            }
+            _ => {
+                html.push_str(&indent);
+                html.push_str(&String::from(value.render(&format.compact())));
+                while let Some(value) = values.next_if(|value| value.is_inline()) {
+                    html.push_str(&String::from(value.render(&format.compact())));
+                }
+            }
+        }
+    }
 }
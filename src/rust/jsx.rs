@@ -10,6 +10,7 @@ use wasm_bindgen::{prelude::*, JsCast};
 
 #[wasm_bindgen]
 extern "C" {
+    #[derive(Clone)]
     pub type Jsx;
 
     #[wasm_bindgen(method, getter, js_name = "$$typeof")]
@@ -25,6 +26,9 @@ extern "C" {
 
     #[wasm_bindgen(method, getter)]
     pub fn children(this: &JsxProps) -> Option<js_sys::Object>;
+
+    #[wasm_bindgen(method, getter, js_name = dangerouslySetInnerHTML)]
+    pub fn dangerously_set_inner_html(this: &JsxProps) -> Option<js_sys::Object>;
 }
 
 impl Jsx {
@@ -14,6 +14,7 @@ fn set_panic_hook() {
 
 mod component;
 mod constants;
+mod events;
 mod html;
 mod jsx;
 mod react;
@@ -37,8 +37,43 @@ extern "C" {
     pub fn escape_html(input: &JsString) -> JsString;
 }
 
+#[wasm_bindgen(module = "/src/js/escapeRawText.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = escapeScriptContent)]
+    pub fn escape_script_content(input: &JsString) -> JsString;
+
+    #[wasm_bindgen(js_name = escapeStyleContent)]
+    pub fn escape_style_content(input: &JsString) -> JsString;
+}
+
+#[wasm_bindgen(module = "/src/js/isBigInt.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = isBigInt)]
+    pub fn is_big_int(value: &JsValue) -> bool;
+}
+
 #[wasm_bindgen(module = "/src/js/ReactUpdater.js")]
 extern "C" {
     #[wasm_bindgen(getter, js_name = ReactUpdater)]
     pub fn react_updater() -> JsValue;
 }
+
+#[wasm_bindgen(module = "/src/js/ReactDispatcher.js")]
+extern "C" {
+    pub type ReactDispatcher;
+
+    #[wasm_bindgen(js_name = ReactDispatcher)]
+    pub fn react_dispatcher(identifier_prefix: Option<String>) -> ReactDispatcher;
+
+    #[wasm_bindgen(method)]
+    pub fn install(this: &ReactDispatcher, react: &React);
+
+    #[wasm_bindgen(method)]
+    pub fn uninstall(this: &ReactDispatcher, react: &React);
+
+    #[wasm_bindgen(method, js_name = prepareToUseHooks)]
+    pub fn prepare_to_use_hooks(this: &ReactDispatcher, tree_id: &str);
+
+    #[wasm_bindgen(method, js_name = finishHooks)]
+    pub fn finish_hooks(this: &ReactDispatcher) -> bool;
+}
@@ -1,86 +1,328 @@
-use crate::{constants::*, html::HTMLElement};
-
 use js_sys::{JsString, Object, Reflect};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
 use wasm_bindgen::{prelude::*, JsCast};
 
-pub fn add_style_to_attributes(
-    value: JsValue,
-    attr_name: JsString,
-    element: &mut HTMLElement,
-) -> Result<(), JsValue> {
-    EMPTY.with(|empty| {
-        SEMICOLON.with(|semicolon| {
-            let attr_value = value.unchecked_into::<js_sys::Object>();
-            let attr_value = Object::keys(&attr_value)
-                .iter()
-                .map(|key| {
-                    let value = Reflect::get(&attr_value, &key).unwrap();
-                    [key, value]
-                })
-                .filter(|[_, v]| !v.is_null())
-                .filter(|[_, v]| !v.is_undefined())
-                .map(map_style_to_css)
-                .collect::<js_sys::Array>()
-                .join(semicolon);
-            if &attr_value != empty {
-                Reflect::set(&element.attributes, &attr_name, &attr_value)?;
+include!(concat!(env!("OUT_DIR"), "/unitless.rs"));
+
+thread_local! {
+    /// Style names are hyphenated only once, because the same few are used
+    /// over and over again.
+    static HYPHENATED_STYLE_NAMES: RefCell<HashMap<String, String>> =
+        RefCell::new(HashMap::new());
+
+    /// Every invalid style name or value is only warned about once.
+    static WARNED_STYLE_NAMES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static WARNED_STYLE_VALUES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// A serialized `style` prop.
+pub struct InlineStyle {
+    pub css: String,
+    /// Whether the declarations can be moved into a stylesheet as they are.
+    pub is_extractable: bool,
+}
+
+/// Serializes the `style` prop. Invalid styles are passed to `warn`, so
+/// that the renderer can add the component stack.
+pub fn style_to_css(value: JsValue, warn: impl Fn(String)) -> Result<InlineStyle, JsValue> {
+    let styles = value.unchecked_into::<Object>();
+    let mut css = String::new();
+    let mut is_extractable = true;
+    for key in Object::keys(&styles).values() {
+        let key = key?;
+        let value = Reflect::get(&styles, &key)?;
+        if value.is_null() || value.is_undefined() {
+            continue;
+        }
+        let style_name = String::from(key.unchecked_into::<JsString>());
+        let is_custom_property = style_name.starts_with("--");
+        if !is_custom_property {
+            warn_valid_style(&style_name, &value, &warn);
+        }
+        if !css.is_empty() {
+            css.push(';');
+        }
+        let start = css.len();
+        write_style(&mut css, &style_name, &value, is_custom_property);
+        is_extractable = is_extractable && is_extractable_declaration(&css[start..]);
+    }
+    Ok(InlineStyle {
+        css,
+        is_extractable,
+    })
+}
+
+/// A declaration can only be moved into a rule of a stylesheet, if it can't
+/// end the rule or the `<style>` element. Braces and `<` are never allowed
+/// and a `;` only in strings and parentheses, where it can't start another
+/// declaration.
+fn is_extractable_declaration(declaration: &str) -> bool {
+    let mut quote = None;
+    let mut depth = 0_usize;
+    let mut chars = declaration.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' | '<' => return false,
+            '\\' => {
+                if matches!(chars.next(), Some('{' | '}' | '<')) {
+                    return false;
+                }
             }
-            Ok(())
-        })
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Inline styles that have been replaced by classes. Every distinct style
+/// gets one class, whose name is derived from a hash of its declarations.
+/// Styles with the same hash get a suffix, so that no class has two rules.
+/// Styles that could break out of their rule stay inline, see
+/// `InlineStyle::is_extractable`.
+#[derive(Default)]
+pub struct ExtractedStyles {
+    rules: Vec<(String, String)>,
+    class_names: HashMap<String, String>,
+    styles: HashMap<String, String>,
+}
+
+impl ExtractedStyles {
+    pub fn class_name(&mut self, css: String) -> String {
+        if let Some(class_name) = self.class_names.get(&css) {
+            return class_name.clone();
+        }
+        let hashed = format!("s{}", to_base_36(hash(&css)));
+        let mut class_name = hashed.clone();
+        let mut suffix = 0;
+        while self.styles.contains_key(&class_name) {
+            suffix += 1;
+            class_name = format!("{}-{}", hashed, suffix);
+        }
+        self.class_names.insert(css.clone(), class_name.clone());
+        self.styles.insert(class_name.clone(), css.clone());
+        self.rules.push((class_name.clone(), css));
+        class_name
+    }
+
+    /// The stylesheet with one rule per class.
+    pub fn to_css(&self) -> String {
+        self.rules
+            .iter()
+            .map(|(class_name, css)| format!(".{}{{{}}}", class_name, css))
+            .collect()
+    }
+}
+
+/// 32 bit FNV-1a, which is stable across renders and processes.
+fn hash(css: &str) -> u32 {
+    css.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
     })
 }
 
-fn map_style_to_css([key, value]: [JsValue; 2]) -> JsString {
-    EMPTY.with(|empty| {
-        CSS_VARIABLE.with(|css_variable| {
-            COLON.with(|colon| {
-                PX.with(|px| {
-                    let mut css_prop: JsString = key.unchecked_into();
-                    let is_custom_css_prop = css_prop.starts_with(css_variable, 0);
-                    css_prop = if is_custom_css_prop {
-                        css_prop
-                    } else {
-                        hyphenate_style_name(css_prop)
-                    };
-                    if let Some(css_val) = value.dyn_ref::<JsString>() {
-                        let css_val = css_val.trim();
-                        css_prop.concat(&colon).concat(&css_val)
-                    } else if let Ok(css_val) = value.dyn_into::<js_sys::Number>() {
-                        let css_val: f64 = css_val.into();
-                        let suffix =
-                            if css_val == 0. || is_unitless(&css_prop) || is_custom_css_prop {
-                                empty.clone()
-                            } else {
-                                px.clone()
-                            };
-                        css_prop
-                            .concat(&colon)
-                            .concat(&css_val.into())
-                            .concat(&suffix)
-                    } else {
-                        empty.clone()
-                    }
-                })
-            })
-        })
+fn to_base_36(mut value: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit(value % 36, 36).unwrap());
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// Same as React's `createMarkupForStyles` for a single style. Booleans
+/// are written as empty value.
+fn write_style(css: &mut String, style_name: &str, value: &JsValue, is_custom_property: bool) {
+    if is_custom_property {
+        css.push_str(style_name);
+    } else {
+        hyphenate_style_name(css, style_name);
+    }
+    css.push(':');
+    if let Some(value) = value.as_string() {
+        css.push_str(value.trim());
+    } else if let Some(value) = value.as_f64() {
+        write_number(css, value);
+        if value != 0. && !is_custom_property && !UNITLESS.contains(style_name) {
+            css.push_str("px");
+        }
+    } else if value.as_bool().is_none() {
+        css.push_str(String::from(value.unchecked_ref::<Object>().to_string()).trim());
+    }
+}
+
+/// Same as React's `warnValidStyle`.
+fn warn_valid_style(style_name: &str, value: &JsValue, warn: &impl Fn(String)) {
+    let warn_once = |warned: &'static std::thread::LocalKey<RefCell<HashSet<String>>>,
+                     key: &str,
+                     err: String| {
+        if warned.with(|warned| warned.borrow_mut().insert(key.to_string())) {
+            warn(err);
+        }
+    };
+    if style_name.contains('-') {
+        warn_once(
+            &WARNED_STYLE_NAMES,
+            style_name,
+            format!(
+                "Unsupported style property {}. Did you mean {}?",
+                style_name,
+                camelize(style_name)
+            ),
+        );
+    } else if is_bad_vendor_prefix(style_name) {
+        let mut chars = style_name.chars();
+        let first = chars.next().unwrap_or_default().to_ascii_uppercase();
+        warn_once(
+            &WARNED_STYLE_NAMES,
+            style_name,
+            format!(
+                "Unsupported vendor-prefixed style property {}. Did you mean {}{}?",
+                style_name,
+                first,
+                chars.as_str()
+            ),
+        );
+    } else if let Some(string) = value.as_string() {
+        if let Some(trimmed) = string.trim_end().strip_suffix(';') {
+            warn_once(
+                &WARNED_STYLE_VALUES,
+                &string,
+                format!(
+                    "Style property values shouldn't contain a semicolon. Try \"{}: {}\" \
+                     instead.",
+                    style_name, trimmed
+                ),
+            );
+        }
+    }
+    if let Some(number) = value.as_f64() {
+        // Same as React, these are only warned about once at all.
+        if number.is_nan() || number.is_infinite() {
+            let value = if number.is_nan() { "NaN" } else { "Infinity" };
+            warn_once(
+                &WARNED_STYLE_VALUES,
+                value,
+                format!(
+                    "`{}` is an invalid value for the `{}` css style property.",
+                    value, style_name
+                ),
+            );
+        }
+    }
+}
+
+/// Prefixes have to start with an upper case letter, except for `ms`.
+fn is_bad_vendor_prefix(style_name: &str) -> bool {
+    ["webkit", "moz", "o"].iter().any(|prefix| {
+        style_name.starts_with(prefix)
+            && style_name[prefix.len()..].starts_with(|c: char| c.is_ascii_uppercase())
     })
 }
 
-fn hyphenate_style_name(css_prop: JsString) -> JsString {
-    UPPER_CASE.with(|upper_case| {
-        UPPER_CASE_REGEXP.with(|upper_case_pattern| {
-            MS.with(|ms| {
-                MS_REGEXP.with(|ms_pattern| {
-                    css_prop
-                        .replace_by_pattern(upper_case_pattern, upper_case)
-                        .to_lower_case()
-                        .replace_by_pattern(ms_pattern, ms)
-                })
-            })
-        })
+/// Same as React's `camelize`, e.g. `backgroundColor` for
+/// `background-color` and `msTransform` for `-ms-transform`.
+fn camelize(style_name: &str) -> String {
+    let style_name = match style_name.strip_prefix("-ms-") {
+        Some(rest) => format!("ms-{}", rest),
+        None => style_name.to_string(),
+    };
+    let mut camelized = String::with_capacity(style_name.len());
+    let mut chars = style_name.chars();
+    while let Some(c) = chars.next() {
+        if c == '-' {
+            if let Some(next) = chars.next() {
+                camelized.extend(next.to_uppercase());
+            }
+        } else {
+            camelized.push(c);
+        }
+    }
+    camelized
+}
+
+/// Same as React's `hyphenateStyleName`, e.g. `-webkit-line-clamp` for
+/// `WebkitLineClamp` and `-ms-transition` for `msTransition`.
+fn hyphenate_style_name(css: &mut String, style_name: &str) {
+    HYPHENATED_STYLE_NAMES.with(|hyphenated_style_names| {
+        let mut hyphenated_style_names = hyphenated_style_names.borrow_mut();
+        if let Some(hyphenated) = hyphenated_style_names.get(style_name) {
+            css.push_str(hyphenated);
+            return;
+        }
+        let mut hyphenated = String::with_capacity(style_name.len() + 4);
+        for c in style_name.chars() {
+            if c.is_ascii_uppercase() {
+                hyphenated.push('-');
+                hyphenated.push(c.to_ascii_lowercase());
+            } else {
+                hyphenated.extend(c.to_lowercase());
+            }
+        }
+        if hyphenated.starts_with("ms-") {
+            hyphenated.insert(0, '-');
+        }
+        css.push_str(&hyphenated);
+        hyphenated_style_names.insert(style_name.to_string(), hyphenated);
     })
 }
 
-fn is_unitless(css_prop: &JsString) -> bool {
-    UNITLESS.with(|unitless| unitless.includes(css_prop, 0))
+/// Formats a number the same way as JS' `Number.prototype.toString`, which
+/// uses the shortest representation that reads back as the same number and
+/// switches to exponential notation for very large and very small numbers.
+fn write_number(css: &mut String, value: f64) {
+    if value.is_nan() {
+        css.push_str("NaN");
+        return;
+    }
+    if value.is_infinite() {
+        css.push_str(if value > 0. { "Infinity" } else { "-Infinity" });
+        return;
+    }
+    if value == 0. {
+        css.push('0');
+        return;
+    }
+    if value < 0. {
+        css.push('-');
+    }
+    // Rust writes the shortest representation as well, e.g. `1.5e-7`.
+    let exponential = format!("{:e}", value.abs());
+    let (mantissa, exponent) = exponential.split_at(exponential.find('e').unwrap());
+    let digits = mantissa.replace('.', "");
+    let exponent = exponent[1..].parse::<i32>().unwrap();
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+    if k <= n && n <= 21 {
+        css.push_str(&digits);
+        css.extend((0..n - k).map(|_| '0'));
+    } else if 0 < n && n <= 21 {
+        css.push_str(&digits[..n as usize]);
+        css.push('.');
+        css.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        css.push_str("0.");
+        css.extend((0..-n).map(|_| '0'));
+        css.push_str(&digits);
+    } else {
+        css.push_str(&digits[..1]);
+        if k > 1 {
+            css.push('.');
+            css.push_str(&digits[1..]);
+        }
+        css.push('e');
+        css.push(if n > 0 { '+' } else { '-' });
+        css.push_str(&(n - 1).abs().to_string());
+    }
 }
@@ -0,0 +1,341 @@
+use std::{cell::RefCell, collections::HashSet};
+
+use wasm_bindgen::JsValue;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PropertyType {
+    /// Props that are handled by the renderer and never become attributes.
+    Reserved,
+    String,
+    /// Strings that also accept booleans, which are stringified.
+    BooleanishString,
+    /// Present with an empty value if true, removed if false.
+    Boolean,
+    /// Like `Boolean`, but a string value is written as is.
+    OverloadedBoolean,
+    /// Removed if the value is not a number.
+    Numeric,
+    /// Removed if the value is not a number greater than zero.
+    PositiveNumeric,
+}
+
+pub struct PropertyInfo {
+    pub attribute_name: &'static str,
+    pub property_type: PropertyType,
+}
+
+impl PropertyInfo {
+    pub fn accepts_booleans(&self) -> bool {
+        matches!(
+            self.property_type,
+            PropertyType::BooleanishString
+                | PropertyType::Boolean
+                | PropertyType::OverloadedBoolean
+        )
+    }
+}
+
+/// SVG attributes, which are written in camel case in JSX, with the name of
+/// the attribute they map to. This includes the attributes in the `xlink` and
+/// `xml` namespaces.
+const SVG_ATTRIBUTES: &[(&str, &str)] = &[
+    ("accentHeight", "accent-height"),
+    ("alignmentBaseline", "alignment-baseline"),
+    ("arabicForm", "arabic-form"),
+    ("baselineShift", "baseline-shift"),
+    ("capHeight", "cap-height"),
+    ("clipPath", "clip-path"),
+    ("clipRule", "clip-rule"),
+    ("colorInterpolation", "color-interpolation"),
+    ("colorInterpolationFilters", "color-interpolation-filters"),
+    ("colorProfile", "color-profile"),
+    ("colorRendering", "color-rendering"),
+    ("dominantBaseline", "dominant-baseline"),
+    ("enableBackground", "enable-background"),
+    ("fillOpacity", "fill-opacity"),
+    ("fillRule", "fill-rule"),
+    ("floodColor", "flood-color"),
+    ("floodOpacity", "flood-opacity"),
+    ("fontFamily", "font-family"),
+    ("fontSize", "font-size"),
+    ("fontSizeAdjust", "font-size-adjust"),
+    ("fontStretch", "font-stretch"),
+    ("fontStyle", "font-style"),
+    ("fontVariant", "font-variant"),
+    ("fontWeight", "font-weight"),
+    ("glyphName", "glyph-name"),
+    ("glyphOrientationHorizontal", "glyph-orientation-horizontal"),
+    ("glyphOrientationVertical", "glyph-orientation-vertical"),
+    ("horizAdvX", "horiz-adv-x"),
+    ("horizOriginX", "horiz-origin-x"),
+    ("imageRendering", "image-rendering"),
+    ("letterSpacing", "letter-spacing"),
+    ("lightingColor", "lighting-color"),
+    ("markerEnd", "marker-end"),
+    ("markerMid", "marker-mid"),
+    ("markerStart", "marker-start"),
+    ("overlinePosition", "overline-position"),
+    ("overlineThickness", "overline-thickness"),
+    ("paintOrder", "paint-order"),
+    ("panose-1", "panose-1"),
+    ("pointerEvents", "pointer-events"),
+    ("renderingIntent", "rendering-intent"),
+    ("shapeRendering", "shape-rendering"),
+    ("stopColor", "stop-color"),
+    ("stopOpacity", "stop-opacity"),
+    ("strikethroughPosition", "strikethrough-position"),
+    ("strikethroughThickness", "strikethrough-thickness"),
+    ("strokeDasharray", "stroke-dasharray"),
+    ("strokeDashoffset", "stroke-dashoffset"),
+    ("strokeLinecap", "stroke-linecap"),
+    ("strokeLinejoin", "stroke-linejoin"),
+    ("strokeMiterlimit", "stroke-miterlimit"),
+    ("strokeOpacity", "stroke-opacity"),
+    ("strokeWidth", "stroke-width"),
+    ("textAnchor", "text-anchor"),
+    ("textDecoration", "text-decoration"),
+    ("textRendering", "text-rendering"),
+    ("underlinePosition", "underline-position"),
+    ("underlineThickness", "underline-thickness"),
+    ("unicodeBidi", "unicode-bidi"),
+    ("unicodeRange", "unicode-range"),
+    ("unitsPerEm", "units-per-em"),
+    ("vAlphabetic", "v-alphabetic"),
+    ("vHanging", "v-hanging"),
+    ("vIdeographic", "v-ideographic"),
+    ("vMathematical", "v-mathematical"),
+    ("vectorEffect", "vector-effect"),
+    ("vertAdvY", "vert-adv-y"),
+    ("vertOriginX", "vert-origin-x"),
+    ("vertOriginY", "vert-origin-y"),
+    ("wordSpacing", "word-spacing"),
+    ("writingMode", "writing-mode"),
+    ("xmlnsXlink", "xmlns:xlink"),
+    ("xHeight", "x-height"),
+    ("xlinkActuate", "xlink:actuate"),
+    ("xlinkArcrole", "xlink:arcrole"),
+    ("xlinkHref", "xlink:href"),
+    ("xlinkRole", "xlink:role"),
+    ("xlinkShow", "xlink:show"),
+    ("xlinkTitle", "xlink:title"),
+    ("xlinkType", "xlink:type"),
+    ("xmlBase", "xml:base"),
+    ("xmlLang", "xml:lang"),
+    ("xmlSpace", "xml:space"),
+];
+
+/// The subset of React's `DOMProperty` table for props that don't map to an
+/// attribute of the same name, or need special handling of their value.
+pub fn get_property_info(name: &str) -> Option<PropertyInfo> {
+    use PropertyType::*;
+
+    let (attribute_name, property_type) = match name {
+        "children" => ("children", Reserved),
+        "dangerouslySetInnerHTML" => ("dangerouslySetInnerHTML", Reserved),
+        "defaultValue" => ("defaultValue", Reserved),
+        "defaultChecked" => ("defaultChecked", Reserved),
+        "innerHTML" => ("innerHTML", Reserved),
+        "suppressContentEditableWarning" => ("suppressContentEditableWarning", Reserved),
+        "suppressHydrationWarning" => ("suppressHydrationWarning", Reserved),
+        "style" => ("style", Reserved),
+        "acceptCharset" => ("accept-charset", String),
+        "className" => ("class", String),
+        "htmlFor" => ("for", String),
+        "httpEquiv" => ("http-equiv", String),
+        "tabIndex" => ("tabindex", String),
+        "crossOrigin" => ("crossorigin", String),
+        "contentEditable" => ("contenteditable", BooleanishString),
+        "draggable" => ("draggable", BooleanishString),
+        "spellCheck" => ("spellcheck", BooleanishString),
+        "value" => ("value", BooleanishString),
+        "allowFullScreen" => ("allowfullscreen", Boolean),
+        "async" => ("async", Boolean),
+        "autoFocus" => ("autofocus", Boolean),
+        "autoPlay" => ("autoplay", Boolean),
+        "controls" => ("controls", Boolean),
+        "default" => ("default", Boolean),
+        "defer" => ("defer", Boolean),
+        "disabled" => ("disabled", Boolean),
+        "disablePictureInPicture" => ("disablepictureinpicture", Boolean),
+        "formNoValidate" => ("formnovalidate", Boolean),
+        "hidden" => ("hidden", Boolean),
+        "loop" => ("loop", Boolean),
+        "noModule" => ("nomodule", Boolean),
+        "noValidate" => ("novalidate", Boolean),
+        "open" => ("open", Boolean),
+        "playsInline" => ("playsinline", Boolean),
+        "readOnly" => ("readonly", Boolean),
+        "required" => ("required", Boolean),
+        "reversed" => ("reversed", Boolean),
+        "scoped" => ("scoped", Boolean),
+        "seamless" => ("seamless", Boolean),
+        "itemScope" => ("itemscope", Boolean),
+        "checked" => ("checked", Boolean),
+        "multiple" => ("multiple", Boolean),
+        "muted" => ("muted", Boolean),
+        "selected" => ("selected", Boolean),
+        "capture" => ("capture", OverloadedBoolean),
+        "download" => ("download", OverloadedBoolean),
+        "cols" => ("cols", PositiveNumeric),
+        "rows" => ("rows", PositiveNumeric),
+        "size" => ("size", PositiveNumeric),
+        "span" => ("span", PositiveNumeric),
+        "rowSpan" => ("rowspan", Numeric),
+        "start" => ("start", Numeric),
+        "autoReverse" => ("autoReverse", BooleanishString),
+        "externalResourcesRequired" => ("externalResourcesRequired", BooleanishString),
+        "focusable" => ("focusable", BooleanishString),
+        "preserveAlpha" => ("preserveAlpha", BooleanishString),
+        _ => match SVG_ATTRIBUTES
+            .iter()
+            .find(|(prop_name, _)| *prop_name == name)
+        {
+            Some((_, attribute_name)) => (*attribute_name, String),
+            None => return None,
+        },
+    };
+    Some(PropertyInfo {
+        attribute_name,
+        property_type,
+    })
+}
+
+/// Same as React's `shouldRemoveAttribute` for values that can't be
+/// written into the markup.
+pub fn should_remove_attribute(name: &str, value: &JsValue, info: Option<&PropertyInfo>) -> bool {
+    if value.is_null() || value.is_undefined() || value.is_function() || value.is_symbol() {
+        return true;
+    }
+    if value.as_bool().is_some() {
+        let accepts_booleans = match info {
+            Some(info) => info.accepts_booleans(),
+            None => {
+                let prefix = name.to_ascii_lowercase();
+                prefix.starts_with("data-") || prefix.starts_with("aria-")
+            }
+        };
+        if !accepts_booleans {
+            return true;
+        }
+    }
+    match info.map(|info| info.property_type) {
+        Some(PropertyType::Boolean) => !value.is_truthy(),
+        Some(PropertyType::OverloadedBoolean) => value.as_bool() == Some(false),
+        Some(PropertyType::Numeric) => to_number(value).is_nan(),
+        Some(PropertyType::PositiveNumeric) => {
+            let number = to_number(value);
+            number.is_nan() || number < 1.
+        }
+        _ => false,
+    }
+}
+
+fn to_number(value: &JsValue) -> f64 {
+    if let Some(number) = value.as_f64() {
+        number
+    } else if let Some(string) = value.as_string() {
+        let string = string.trim();
+        if string.is_empty() {
+            0.
+        } else {
+            string.parse().unwrap_or(f64::NAN)
+        }
+    } else {
+        f64::NAN
+    }
+}
+
+/// Same as React's `isCustomComponent`. A tag with a hyphen or an element with
+/// an `is` prop is a custom element, except for some SVG and MathML tags that
+/// happen to contain a hyphen.
+pub fn is_custom_component(tag: &str, is: &JsValue) -> bool {
+    if !tag.contains('-') {
+        return is.is_string();
+    }
+    !matches!(
+        tag,
+        "annotation-xml"
+            | "color-profile"
+            | "font-face"
+            | "font-face-src"
+            | "font-face-uri"
+            | "font-face-format"
+            | "font-face-name"
+            | "missing-glyph"
+    )
+}
+
+/// Props that are never written as attributes of a custom element.
+pub fn is_reserved_custom_component_prop(name: &str) -> bool {
+    matches!(
+        name,
+        "children"
+            | "dangerouslySetInnerHTML"
+            | "suppressContentEditableWarning"
+            | "suppressHydrationWarning"
+            | "style"
+            | "key"
+            | "ref"
+    )
+}
+
+thread_local! {
+    static ILLEGAL_ATTRIBUTE_NAMES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static VALIDATED_ATTRIBUTE_NAMES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Checks the name against React's `VALID_ATTRIBUTE_NAME_REGEX`, so that
+/// props taken from user data can't inject markup. Every invalid name is
+/// only warned about once.
+pub fn is_attribute_name_safe(attribute_name: &str) -> bool {
+    if VALIDATED_ATTRIBUTE_NAMES.with(|names| names.borrow().contains(attribute_name)) {
+        return true;
+    }
+    if ILLEGAL_ATTRIBUTE_NAMES.with(|names| names.borrow().contains(attribute_name)) {
+        return false;
+    }
+    let mut chars = attribute_name.chars();
+    let is_valid = match chars.next() {
+        Some(first) => is_attribute_name_start_char(first) && chars.all(is_attribute_name_char),
+        None => false,
+    };
+    if is_valid {
+        VALIDATED_ATTRIBUTE_NAMES.with(|names| {
+            names.borrow_mut().insert(attribute_name.to_string());
+        });
+    } else {
+        ILLEGAL_ATTRIBUTE_NAMES.with(|names| {
+            names.borrow_mut().insert(attribute_name.to_string());
+        });
+        let err = format!("Invalid attribute name: `{}`", attribute_name);
+        web_sys::console::error_1(&err.into());
+    }
+    is_valid
+}
+
+fn is_attribute_name_start_char(c: char) -> bool {
+    matches!(
+        c,
+        ':' | 'A'..='Z'
+            | '_'
+            | 'a'..='z'
+            | '\u{C0}'..='\u{D6}'
+            | '\u{D8}'..='\u{F6}'
+            | '\u{F8}'..='\u{2FF}'
+            | '\u{370}'..='\u{37D}'
+            | '\u{37F}'..='\u{1FFF}'
+            | '\u{200C}'..='\u{200D}'
+            | '\u{2070}'..='\u{218F}'
+            | '\u{2C00}'..='\u{2FEF}'
+            | '\u{3001}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FDCF}'
+            | '\u{FDF0}'..='\u{FFFD}'
+    )
+}
+
+fn is_attribute_name_char(c: char) -> bool {
+    match c {
+        '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}' => true,
+        _ => is_attribute_name_start_char(c),
+    }
+}
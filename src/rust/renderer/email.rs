@@ -0,0 +1,141 @@
+use crate::{
+    html::HTMLValue,
+    renderer::stylesheet::{split_top_level, Stylesheet},
+};
+
+use std::{cell::RefCell, collections::HashSet};
+
+/// Properties that at least one of the major email clients ignores, see
+/// https://www.caniemail.com.
+const UNSUPPORTED_PROPERTIES: &[&str] = &[
+    "animation",
+    "backdrop-filter",
+    "box-shadow",
+    "clip-path",
+    "filter",
+    "flex",
+    "flex-direction",
+    "flex-wrap",
+    "gap",
+    "grid",
+    "grid-template-areas",
+    "grid-template-columns",
+    "grid-template-rows",
+    "mask",
+    "object-fit",
+    "position",
+    "transform",
+    "transition",
+];
+
+const UNSUPPORTED_DISPLAYS: &[&str] = &["flex", "grid", "inline-flex", "inline-grid"];
+
+/// Layout tables need the spacing that some clients add by default reset,
+/// and screen readers should not announce them as data tables.
+const TABLE_DEFAULTS: &[(&str, &str)] = &[
+    ("border", "0"),
+    ("cellpadding", "0"),
+    ("cellspacing", "0"),
+    ("role", "presentation"),
+];
+
+thread_local! {
+    /// Every unsupported declaration is only warned about once.
+    static WARNED_DECLARATIONS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Writes the declarations of the stylesheet's rules for single classes into
+/// the `style` attribute of every element with one of these classes, because
+/// many email clients ignore `<style>`. Declarations of the `style` prop come
+/// last, so that they still win.
+pub fn inline_class_styles(
+    values: &mut [HTMLValue],
+    stylesheet: &Stylesheet,
+    warn: &impl Fn(String),
+) {
+    for value in values {
+        if let HTMLValue::Element(element) = value {
+            let classes = element
+                .attributes
+                .get("class")
+                .map(|class| {
+                    String::from(class)
+                        .split_ascii_whitespace()
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let css = stylesheet
+                .class_declarations(&classes)
+                .into_iter()
+                .map(|declarations| declarations.trim().trim_end_matches(';').trim_end())
+                .filter(|declarations| !declarations.is_empty())
+                .collect::<Vec<_>>()
+                .join(";");
+            if !css.is_empty() {
+                check_email_css(&css, warn);
+                let style = match element.attributes.get("style").map(String::from) {
+                    Some(style) if !style.is_empty() => format!("{};{}", css, style),
+                    _ => css,
+                };
+                element
+                    .attributes
+                    .set("style".into(), style.as_str().into());
+            }
+            inline_class_styles(&mut element.children, stylesheet, warn);
+        }
+    }
+}
+
+/// Adds the `TABLE_DEFAULTS` that a table doesn't set itself, because tables
+/// are the only layout that all email clients render the same.
+pub fn add_table_defaults(values: &mut [HTMLValue]) {
+    for value in values {
+        if let HTMLValue::Element(element) = value {
+            if element.tag == "table" {
+                for (name, default) in TABLE_DEFAULTS {
+                    // Props like `cellPadding` are written as they are named.
+                    let is_set = element
+                        .attributes
+                        .iter()
+                        .any(|(other, _)| String::from(other.clone()).eq_ignore_ascii_case(name));
+                    if !is_set {
+                        element.attributes.set((*name).into(), (*default).into());
+                    }
+                }
+            }
+            add_table_defaults(&mut element.children);
+        }
+    }
+}
+
+/// Warns about the declarations that not all major email clients support.
+pub fn check_email_css(css: &str, warn: &impl Fn(String)) {
+    for declaration in split_top_level(css, ';') {
+        let (property, value) = match declaration.split_once(':') {
+            Some((property, value)) => (
+                property.trim().to_ascii_lowercase(),
+                value.trim().to_ascii_lowercase(),
+            ),
+            None => continue,
+        };
+        let err = if UNSUPPORTED_PROPERTIES.contains(&property.as_str()) {
+            format!(
+                "The CSS property `{}` is not supported by all major email clients.",
+                property
+            )
+        } else if property == "display" && UNSUPPORTED_DISPLAYS.contains(&value.as_str()) {
+            format!(
+                "The CSS value `display: {}` is not supported by all major email clients.",
+                value
+            )
+        } else if property.starts_with("--") || value.contains("var(") {
+            "CSS variables are not supported by all major email clients.".to_string()
+        } else {
+            continue;
+        };
+        if WARNED_DECLARATIONS.with(|warned| warned.borrow_mut().insert(err.clone())) {
+            warn(err);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::html::{HTMLElement, HTMLValue};
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::JsValue;
+
+/// Elements that belong into `<head>`, collected from anywhere in the tree.
+/// Like React 19's hoistables, a later `<title>`, `<base>` or `<meta>` with
+/// the same key replaces an earlier one, while stylesheets and preloads are
+/// only written for their first occurrence.
+#[derive(Default)]
+pub struct HeadElements {
+    charset: Option<HTMLElement>,
+    base: Option<HTMLElement>,
+    title: Option<HTMLElement>,
+    metas: Vec<(Option<String>, HTMLElement)>,
+    preloads: Vec<(String, HTMLElement)>,
+    /// Stylesheets grouped by their precedence, in order of appearance.
+    stylesheets: Vec<(String, Vec<(String, HTMLElement)>)>,
+}
+
+impl HeadElements {
+    /// Elements with `itemProp` belong to their microdata scope, so they are
+    /// never moved.
+    pub fn is_hoistable(tag: &str, props: &Object) -> Result<bool, JsValue> {
+        let item_prop = Reflect::get(props, &"itemProp".into())?;
+        if !item_prop.is_null() && !item_prop.is_undefined() {
+            return Ok(false);
+        }
+        Ok(match tag {
+            "title" | "meta" | "base" => true,
+            "link" => matches!(
+                get_string(props, "rel")?.as_deref(),
+                Some("stylesheet") | Some("preload")
+            ),
+            _ => false,
+        })
+    }
+
+    pub fn insert(&mut self, element: HTMLElement, props: &Object) -> Result<(), JsValue> {
+        match String::from(element.tag.clone()).as_str() {
+            "title" => self.title = Some(element),
+            "base" => self.base = Some(element),
+            "meta" => {
+                if get_string(props, "charSet")?.is_some() {
+                    self.charset = Some(element);
+                } else {
+                    self.insert_meta(element, props)?;
+                }
+            }
+            "link" => {
+                let href = get_string(props, "href")?.unwrap_or_default();
+                if get_string(props, "rel")?.as_deref() == Some("preload") {
+                    let key = format!("{}:{}", get_string(props, "as")?.unwrap_or_default(), href);
+                    if !self.preloads.iter().any(|(preload, _)| *preload == key) {
+                        self.preloads.push((key, element));
+                    }
+                } else {
+                    self.insert_stylesheet(href, element, props)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Takes out the charset and the viewport, which come before anything
+    /// else in `<head>`, even before resources.
+    pub fn take_first(&mut self) -> Vec<HTMLValue> {
+        let viewport = self
+            .metas
+            .iter()
+            .position(|(key, _)| key.as_deref() == Some("name:viewport"))
+            .map(|index| self.metas.remove(index).1);
+        self.charset
+            .take()
+            .into_iter()
+            .chain(viewport)
+            .map(HTMLValue::Element)
+            .collect()
+    }
+
+    /// The elements in the order they should appear in `<head>`. The
+    /// charset has to be within the first bytes of the document and `<base>`
+    /// has to come before any URL it applies to.
+    pub fn into_values(self) -> Vec<HTMLValue> {
+        let metas = self.metas.into_iter().map(|(_, element)| element);
+        let preloads = self.preloads.into_iter().map(|(_, element)| element);
+        let stylesheets = self
+            .stylesheets
+            .into_iter()
+            .flat_map(|(_, stylesheets)| stylesheets.into_iter().map(|(_, element)| element));
+        self.charset
+            .into_iter()
+            .chain(self.base)
+            .chain(self.title)
+            .chain(metas)
+            .chain(preloads)
+            .chain(stylesheets)
+            .map(HTMLValue::Element)
+            .collect()
+    }
+
+    fn insert_meta(&mut self, element: HTMLElement, props: &Object) -> Result<(), JsValue> {
+        let mut key = None;
+        for name in &["name", "property", "httpEquiv"] {
+            if let Some(value) = get_string(props, name)? {
+                key = Some(format!("{}:{}", name, value));
+                break;
+            }
+        }
+        let existing = key.as_ref().and_then(|key| {
+            self.metas
+                .iter_mut()
+                .find(|(meta, _)| meta.as_ref() == Some(key))
+        });
+        match existing {
+            Some(meta) => meta.1 = element,
+            None => self.metas.push((key, element)),
+        }
+        Ok(())
+    }
+
+    /// The `precedence` prop is written as `data-precedence`, same as React.
+    fn insert_stylesheet(
+        &mut self,
+        href: String,
+        mut element: HTMLElement,
+        props: &Object,
+    ) -> Result<(), JsValue> {
+        if self
+            .stylesheets
+            .iter()
+            .any(|(_, stylesheets)| stylesheets.iter().any(|(other, _)| *other == href))
+        {
+            return Ok(());
+        }
+        let precedence = get_string(props, "precedence")?.unwrap_or_default();
+        if !precedence.is_empty() {
+            element.attributes.remove("precedence");
+            element
+                .attributes
+                .set("data-precedence".into(), precedence.as_str().into());
+        }
+        match self
+            .stylesheets
+            .iter_mut()
+            .find(|(other, _)| *other == precedence)
+        {
+            Some((_, stylesheets)) => stylesheets.push((href, element)),
+            None => self.stylesheets.push((precedence, vec![(href, element)])),
+        }
+        Ok(())
+    }
+}
+
+fn get_string(props: &Object, name: &str) -> Result<Option<String>, JsValue> {
+    Ok(Reflect::get(props, &name.into())?.as_string())
+}
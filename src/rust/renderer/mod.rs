@@ -1,6 +1,11 @@
 use crate::{
+    events::get_registration,
     jsx::{Jsx, JsxProps},
     react::ReactComponent,
+    renderer::{
+        dom_property::{is_custom_component, is_reserved_custom_component_prop},
+        namespace::Namespace,
+    },
 };
 
 use js_sys::JsString;
@@ -8,14 +13,22 @@ use wasm_bindgen::{prelude::*, JsCast};
 use web_sys::{Document, Element};
 
 pub mod css;
+pub mod dom_property;
+pub mod email;
+pub mod head;
+pub mod namespace;
+pub mod options;
+pub mod resources;
 pub mod server;
+pub mod stylesheet;
+pub mod tree_context;
 
 #[wasm_bindgen]
 #[allow(dead_code)]
 pub fn render(jsx: &Jsx) -> Result<(), JsValue> {
     let window = web_sys::window().expect("no global `window` exists");
     let document = window.document().expect("should have a document on window");
-    if let Some(element) = render_jsx(jsx, &document)? {
+    if let Some(element) = render_jsx(jsx, &document, Namespace::default())? {
         let body = document.body().expect("document should have a body");
         body.append_child(&element)?;
         web_sys::console::log_1(&element);
@@ -24,7 +37,11 @@ pub fn render(jsx: &Jsx) -> Result<(), JsValue> {
     Ok(())
 }
 
-fn render_jsx(jsx: &Jsx, document: &Document) -> Result<Option<Element>, JsValue> {
+fn render_jsx(
+    jsx: &Jsx,
+    document: &Document,
+    namespace: Namespace,
+) -> Result<Option<Element>, JsValue> {
     #[cfg(debug_assertions)]
     web_sys::console::log_2(&"RENDER".into(), jsx);
 
@@ -41,7 +58,7 @@ fn render_jsx(jsx: &Jsx, document: &Document) -> Result<Option<Element>, JsValue
             if jsx.is_null() {
                 Ok(None)
             } else {
-                render_jsx(&jsx.unchecked_into::<Jsx>(), document)
+                render_jsx(&jsx.unchecked_into::<Jsx>(), document, namespace)
             }
         }
         ReactComponent::Functional(function) => {
@@ -53,27 +70,38 @@ fn render_jsx(jsx: &Jsx, document: &Document) -> Result<Option<Element>, JsValue
                 .unchecked_into();
             #[cfg(debug_assertions)]
             web_sys::console::log_2(&"FUNCTIONAL COMPONENT".into(), &jsx);
-            render_jsx(&jsx, document)
+            render_jsx(&jsx, document, namespace)
         }
         ReactComponent::Intrinsic(intrinsic) => {
             #[cfg(debug_assertions)]
             web_sys::console::log_2(&"INTRINSIC".into(), &intrinsic.clone().into());
 
-            let element = document.create_element(String::from(intrinsic).as_ref())?;
+            let tag = String::from(intrinsic);
+            let namespace = namespace.of_element(&tag);
+            let element = match namespace {
+                Namespace::Html => document.create_element(&tag)?,
+                _ => document.create_element_ns(Some(namespace.uri()), &tag)?,
+            };
+            let namespace = namespace.of_children(&tag);
 
             let props = jsx.props();
             let props = props.unchecked_ref::<JsxProps>();
 
             #[cfg(debug_assertions)]
-            web_sys::console::log_2(&"PROPS".into(), &props);
+            web_sys::console::log_2(&"PROPS".into(), props);
+
+            add_event_listeners(&element, props)?;
+            if is_custom_component(&tag, &js_sys::Reflect::get(props, &"is".into())?) {
+                set_custom_element_props(&element, props)?;
+            }
 
             if let Some(children) = props.children() {
                 if let Some(children) = children.dyn_ref::<js_sys::Array>() {
                     children.for_each(&mut |val: JsValue, _index, _array| {
-                        render_intrinsic(val.into(), &element, document);
+                        render_intrinsic(val.into(), &element, document, namespace);
                     });
                 } else {
-                    render_intrinsic(children, &element, document);
+                    render_intrinsic(children, &element, document, namespace);
                 }
             }
             Ok(Some(element))
@@ -82,18 +110,84 @@ fn render_jsx(jsx: &Jsx, document: &Document) -> Result<Option<Element>, JsValue
     }
 }
 
-fn render_intrinsic(js_val: js_sys::Object, element: &Element, document: &Document) {
+fn add_event_listeners(element: &Element, props: &JsxProps) -> Result<(), JsValue> {
+    for prop in js_sys::Object::keys(props.unchecked_ref()).values() {
+        let key: JsString = prop?.unchecked_into();
+        if let Some(registration) = get_registration(&String::from(key.clone())) {
+            let listener = js_sys::Reflect::get(props, &key)?;
+            if let Some(listener) = listener.dyn_ref::<js_sys::Function>() {
+                element.add_event_listener_with_callback_and_bool(
+                    registration.dom_event_name,
+                    listener,
+                    registration.capture,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Same as React 19, props of custom elements are assigned as properties if
+/// the element defines them, functions starting with `on` are added as
+/// listeners for custom events and everything else becomes an attribute.
+fn set_custom_element_props(element: &Element, props: &JsxProps) -> Result<(), JsValue> {
+    for prop in js_sys::Object::keys(props.unchecked_ref()).values() {
+        let key: JsString = prop?.unchecked_into();
+        let name = String::from(key.clone());
+        if is_reserved_custom_component_prop(&name) || get_registration(&name).is_some() {
+            continue;
+        }
+        let value = js_sys::Reflect::get(props, &key)?;
+        if let Some(event_name) = name.strip_prefix("on") {
+            if let Some(listener) = value.dyn_ref::<js_sys::Function>() {
+                let (event_name, capture) = match event_name.strip_suffix("Capture") {
+                    Some(event_name) => (event_name, true),
+                    None => (event_name, false),
+                };
+                element.add_event_listener_with_callback_and_bool(event_name, listener, capture)?;
+                continue;
+            }
+        }
+        let attribute_name = if name == "className" {
+            "class"
+        } else if js_sys::Reflect::has(element, &key)? {
+            js_sys::Reflect::set(element, &key, &value)?;
+            continue;
+        } else {
+            &name
+        };
+        if value.as_bool() == Some(true) {
+            element.set_attribute(attribute_name, "")?;
+        } else if !(value.is_null()
+            || value.is_undefined()
+            || value.is_function()
+            || value.is_symbol()
+            || value.as_bool() == Some(false))
+        {
+            let value = value.unchecked_ref::<js_sys::Object>().to_string();
+            element.set_attribute(attribute_name, &String::from(value))?;
+        }
+    }
+    Ok(())
+}
+
+fn render_intrinsic(
+    js_val: js_sys::Object,
+    element: &Element,
+    document: &Document,
+    namespace: Namespace,
+) {
     match js_val.dyn_ref::<JsString>() {
         Some(js_string) => {
             element
-                .insert_adjacent_html("beforeend".into(), &String::from(js_string))
+                .insert_adjacent_html("beforeend", &String::from(js_string))
                 .expect("insert_adjacent_html");
         }
         None => {
             let jsx = js_val.unchecked_ref::<Jsx>();
-            if let Ok(Some(child_element)) = render_jsx(jsx, document) {
+            if let Ok(Some(child_element)) = render_jsx(jsx, document, namespace) {
                 element
-                    .insert_adjacent_element("beforeend".into(), &child_element)
+                    .insert_adjacent_element("beforeend", &child_element)
                     .expect("insert_adjacent_element");
             }
         }
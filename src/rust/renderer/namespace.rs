@@ -0,0 +1,46 @@
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const MATH_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// The namespace an element is created in. Elements inherit the namespace
+/// of their parent, except for `svg` and `math` which start a new one and
+/// `foreignObject` which switches back to HTML for its children.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Namespace {
+    #[default]
+    Html,
+    MathMl,
+    Svg,
+}
+
+impl Namespace {
+    pub fn uri(self) -> &'static str {
+        match self {
+            Namespace::Html => HTML_NAMESPACE,
+            Namespace::MathMl => MATH_NAMESPACE,
+            Namespace::Svg => SVG_NAMESPACE,
+        }
+    }
+
+    /// The namespace of an element with the given tag inside of this one.
+    pub fn of_element(self, tag: &str) -> Namespace {
+        match self {
+            Namespace::Html => match tag {
+                "svg" => Namespace::Svg,
+                "math" => Namespace::MathMl,
+                _ => Namespace::Html,
+            },
+            namespace => namespace,
+        }
+    }
+
+    /// The namespace of the children of an element with the given tag,
+    /// that has been created in this namespace.
+    pub fn of_children(self, tag: &str) -> Namespace {
+        match self {
+            Namespace::Svg if tag == "foreignObject" => Namespace::Html,
+            namespace => namespace,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    pub type RenderOptions;
+
+    #[wasm_bindgen(method, getter, js_name = identifierPrefix)]
+    pub fn identifier_prefix(this: &RenderOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = bootstrapScripts)]
+    pub fn bootstrap_scripts(this: &RenderOptions) -> Option<js_sys::Array>;
+
+    #[wasm_bindgen(method, getter, js_name = bootstrapModules)]
+    pub fn bootstrap_modules(this: &RenderOptions) -> Option<js_sys::Array>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn nonce(this: &RenderOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = extractStyles)]
+    pub fn extract_styles(this: &RenderOptions) -> Option<bool>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn stylesheet(this: &RenderOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn pretty(this: &RenderOptions) -> Option<bool>;
+
+    #[wasm_bindgen(method, getter, js_name = printWidth)]
+    pub fn print_width(this: &RenderOptions) -> Option<u32>;
+
+    #[wasm_bindgen(method, getter, js_name = sortAttributes)]
+    pub fn sort_attributes(this: &RenderOptions) -> Option<bool>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn xml(this: &RenderOptions) -> Option<bool>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn ascii(this: &RenderOptions) -> Option<bool>;
+}
@@ -0,0 +1,263 @@
+use crate::html::{Attributes, HTMLElement, HTMLValue};
+
+use std::{cell::RefCell, collections::HashSet};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    pub type ResourceOptions;
+
+    #[wasm_bindgen(method, getter, js_name = "as")]
+    pub fn as_(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = crossOrigin)]
+    pub fn cross_origin(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = fetchPriority)]
+    pub fn fetch_priority(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn integrity(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn nonce(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn precedence(this: &ResourceOptions) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = "type")]
+    pub fn type_(this: &ResourceOptions) -> Option<String>;
+}
+
+thread_local! {
+    /// Resources of the render that is currently running. Calls outside of a
+    /// render are ignored, same as in React.
+    static RESOURCES: RefCell<Option<Resources>> = const { RefCell::new(None) };
+}
+
+/// Resources that components requested with `preload`, `preinit`,
+/// `preconnect` and `prefetchDNS`. Every resource is only written once.
+#[derive(Default)]
+pub struct Resources {
+    preconnects: Vec<(String, HTMLElement)>,
+    preloads: Vec<(String, HTMLElement)>,
+    /// Stylesheets grouped by their precedence, in order of appearance.
+    stylesheets: Vec<(String, Vec<(String, HTMLElement)>)>,
+    scripts: Vec<(String, HTMLElement)>,
+}
+
+impl Resources {
+    /// Starts collecting resources for a new render and returns the ones of
+    /// a render that was running before, if any.
+    pub fn start() -> Option<Resources> {
+        RESOURCES.with(|resources| resources.replace(Some(Resources::default())))
+    }
+
+    /// Stops collecting and returns the resources of the current render.
+    pub fn finish(previous: Option<Resources>) -> Resources {
+        RESOURCES
+            .with(|resources| resources.replace(previous))
+            .unwrap_or_default()
+    }
+
+    /// The tags in the order the browser should see them. Connections are
+    /// opened first and preloads of resources that are initialized anyway
+    /// are left out.
+    pub fn into_values(self) -> Vec<HTMLValue> {
+        let stylesheets = self
+            .stylesheets
+            .into_iter()
+            .flat_map(|(_, stylesheets)| stylesheets);
+        let initialized = stylesheets
+            .map(|(href, element)| (format!("style:{}", href), element))
+            .chain(
+                self.scripts
+                    .into_iter()
+                    .map(|(href, element)| (format!("script:{}", href), element)),
+            )
+            .collect::<Vec<_>>();
+        let initialized_keys = initialized
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<HashSet<_>>();
+        let preloads = self
+            .preloads
+            .into_iter()
+            .filter(|(key, _)| !initialized_keys.contains(key));
+        self.preconnects
+            .into_iter()
+            .chain(preloads)
+            .chain(initialized)
+            .map(|(_, element)| HTMLValue::Element(element))
+            .collect()
+    }
+
+    fn with(f: impl FnOnce(&mut Resources) -> Result<(), JsValue>) -> Result<(), JsValue> {
+        RESOURCES.with(|resources| match resources.borrow_mut().as_mut() {
+            Some(resources) => f(resources),
+            None => Ok(()),
+        })
+    }
+}
+
+#[wasm_bindgen(js_name = prefetchDNS)]
+#[allow(dead_code)]
+pub fn prefetch_dns(href: String) -> Result<(), JsValue> {
+    Resources::with(|resources| {
+        let key = format!("dns-prefetch:{}", href);
+        if !contains(&resources.preconnects, &key) {
+            let element = link(&[("rel", Some("dns-prefetch".into())), ("href", Some(href))]);
+            resources.preconnects.push((key, element));
+        }
+        Ok(())
+    })
+}
+
+#[wasm_bindgen(js_name = preconnect)]
+#[allow(dead_code)]
+pub fn preconnect(href: String, options: Option<ResourceOptions>) -> Result<(), JsValue> {
+    Resources::with(|resources| {
+        let cross_origin = cross_origin(options.as_ref());
+        let key = format!(
+            "preconnect:{}:{}",
+            cross_origin.as_deref().unwrap_or("null"),
+            href
+        );
+        if !contains(&resources.preconnects, &key) {
+            let element = link(&[
+                ("rel", Some("preconnect".into())),
+                ("href", Some(href)),
+                ("crossorigin", cross_origin),
+            ]);
+            resources.preconnects.push((key, element));
+        }
+        Ok(())
+    })
+}
+
+#[wasm_bindgen(js_name = preload)]
+#[allow(dead_code)]
+pub fn preload(href: String, options: ResourceOptions) -> Result<(), JsValue> {
+    let as_ = match options.as_() {
+        Some(as_) => as_,
+        None => return Ok(()),
+    };
+    Resources::with(|resources| {
+        let key = format!("{}:{}", as_, href);
+        if !contains(&resources.preloads, &key) {
+            let element = link(&[
+                ("rel", Some("preload".into())),
+                ("href", Some(href)),
+                ("as", Some(as_)),
+                ("crossorigin", cross_origin(Some(&options))),
+                ("integrity", options.integrity()),
+                ("type", options.type_()),
+                ("nonce", options.nonce()),
+                ("fetchpriority", options.fetch_priority()),
+            ]);
+            resources.preloads.push((key, element));
+        }
+        Ok(())
+    })
+}
+
+/// Loads and executes a stylesheet or script as early as possible.
+#[wasm_bindgen(js_name = preinit)]
+#[allow(dead_code)]
+pub fn preinit(href: String, options: ResourceOptions) -> Result<(), JsValue> {
+    Resources::with(|resources| match options.as_().as_deref() {
+        Some("style") => {
+            if resources
+                .stylesheets
+                .iter()
+                .any(|(_, stylesheets)| contains(stylesheets, &href))
+            {
+                return Ok(());
+            }
+            let precedence = options.precedence().unwrap_or_else(|| "default".into());
+            let element = link(&[
+                ("rel", Some("stylesheet".into())),
+                ("href", Some(href.clone())),
+                ("data-precedence", Some(precedence.clone())),
+                ("crossorigin", cross_origin(Some(&options))),
+                ("integrity", options.integrity()),
+                ("fetchpriority", options.fetch_priority()),
+            ]);
+            match resources
+                .stylesheets
+                .iter_mut()
+                .find(|(other, _)| *other == precedence)
+            {
+                Some((_, stylesheets)) => stylesheets.push((href, element)),
+                None => resources
+                    .stylesheets
+                    .push((precedence, vec![(href, element)])),
+            }
+            Ok(())
+        }
+        Some("script") => {
+            if !contains(&resources.scripts, &href) {
+                let mut attributes = Attributes::new();
+                attributes.set("src".into(), href.as_str().into());
+                attributes.set_boolean("async".into());
+                set_attributes(
+                    &mut attributes,
+                    &[
+                        ("crossorigin", cross_origin(Some(&options))),
+                        ("integrity", options.integrity()),
+                        ("nonce", options.nonce()),
+                        ("fetchpriority", options.fetch_priority()),
+                    ],
+                );
+                let element = HTMLElement {
+                    tag: "script".into(),
+                    attributes,
+                    children: Vec::new(),
+                };
+                resources.scripts.push((href, element));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    })
+}
+
+/// Same as React, `use-credentials` is kept and every other value means
+/// `anonymous`, which is written as empty attribute.
+fn cross_origin(options: Option<&ResourceOptions>) -> Option<String> {
+    options
+        .and_then(|options| options.cross_origin())
+        .map(|cross_origin| {
+            if cross_origin == "use-credentials" {
+                cross_origin
+            } else {
+                "".into()
+            }
+        })
+}
+
+fn contains(resources: &[(String, HTMLElement)], key: &str) -> bool {
+    resources.iter().any(|(other, _)| other == key)
+}
+
+fn link(attributes_list: &[(&str, Option<String>)]) -> HTMLElement {
+    HTMLElement {
+        tag: "link".into(),
+        attributes: attributes(attributes_list),
+        children: Vec::new(),
+    }
+}
+
+fn attributes(attributes_list: &[(&str, Option<String>)]) -> Attributes {
+    let mut attributes = Attributes::new();
+    set_attributes(&mut attributes, attributes_list);
+    attributes
+}
+
+fn set_attributes(attributes: &mut Attributes, attributes_list: &[(&str, Option<String>)]) {
+    for (name, value) in attributes_list {
+        if let Some(value) = value {
+            attributes.set((*name).into(), value.as_str().into());
+        }
+    }
+}
@@ -1,13 +1,33 @@
 use crate::{
     constants::*,
-    html::{HTMLElement, HTMLValue},
+    events::{get_possible_registration_name, get_registration, is_event_handler_name},
+    html::{
+        is_ascii, render_values, Attributes, ContentModel, Format, HTMLElement, HTMLValue,
+        HTMLWriter, Pretty,
+    },
     jsx::{Jsx, JsxProps},
-    react::{react_updater, React, ReactComponent},
+    react::{
+        escape_style_content, is_big_int, react_dispatcher, react_updater, React, ReactComponent,
+        ReactDispatcher,
+    },
     react_is::ReactIs,
-    renderer::css::add_style_to_attributes,
+    renderer::{
+        css::{style_to_css, ExtractedStyles},
+        dom_property::{
+            get_property_info, is_attribute_name_safe, is_custom_component,
+            is_reserved_custom_component_prop, should_remove_attribute, PropertyInfo, PropertyType,
+        },
+        email::{add_table_defaults, check_email_css, inline_class_styles},
+        head::HeadElements,
+        namespace::{Namespace, XLINK_NAMESPACE},
+        options::RenderOptions,
+        resources::Resources,
+        stylesheet::{RenderedElement, Stylesheet},
+        tree_context::TreeContext,
+    },
 };
 
-use js_sys::{JsString, Object, Reflect};
+use js_sys::{Array, JsString, Object, Reflect};
 use wasm_bindgen::{prelude::*, JsCast};
 
 #[wasm_bindgen(js_name = renderToStaticMarkup)]
@@ -16,9 +36,14 @@ pub fn render_to_static_markup(
     react: &React,
     react_is: &ReactIs,
     jsx: JsValue,
+    options: Option<RenderOptions>,
 ) -> Result<JsString, JsValue> {
     let updater = react_updater();
-    render_server_side(react, react_is, &updater, jsx, true)
+    let mode = RenderMode {
+        is_static: true,
+        ..RenderMode::default()
+    };
+    render_server_side(react, react_is, &updater, jsx, mode, options).map(|result| result.html)
 }
 
 #[wasm_bindgen(js_name = renderToString)]
@@ -27,381 +52,1201 @@ pub fn render_to_string(
     react: &React,
     react_is: &ReactIs,
     jsx: JsValue,
+    options: Option<RenderOptions>,
 ) -> Result<JsString, JsValue> {
     let updater = react_updater();
-    render_server_side(react, react_is, &updater, jsx, false)
+    let mode = RenderMode::default();
+    render_server_side(react, react_is, &updater, jsx, mode, options).map(|result| result.html)
 }
 
-pub fn render_server_side(
+/// Same as `renderToStaticMarkup`, but for HTML email. The rules of the
+/// `stylesheet` option for single classes are written into `style`
+/// attributes and CSS that email clients don't support is warned about.
+#[wasm_bindgen(js_name = renderToEmailMarkup)]
+#[allow(dead_code)]
+pub fn render_to_email_markup(
     react: &React,
     react_is: &ReactIs,
-    updater: &JsValue,
     jsx: JsValue,
-    is_static: bool,
+    options: Option<RenderOptions>,
 ) -> Result<JsString, JsValue> {
-    if react.is_valid_element(&jsx) {
-        let jsx = jsx.unchecked_ref::<Jsx>();
-
-        let html = render_jsx_to_string(
-            react,
-            react_is,
-            updater,
-            None,
-            jsx,
-            Object::new(),
-            is_static,
-            true,
-        )?;
-
-        match html {
-            Some(html) => Ok(html.render()),
-            None => Ok("".into()),
-        }
-    } else if jsx.is_object() {
-        let obj = jsx.unchecked_into::<Object>();
-        let mut err =
-            "Objects are not valid as a React child (found: object with keys {".to_string();
-        Object::keys(&obj).for_each(&mut |key, index, _| {
-            if index != 0 {
-                err.push_str(", ");
-            }
-            let key: JsString = key.into();
-            let key: String = key.into();
-            err.push_str(&key);
-        });
-        err.push_str("})");
-        Err(err.into())
-    } else {
-        // TODO print error
-        Err("".into())
+    let updater = react_updater();
+    let mode = RenderMode {
+        is_static: true,
+        is_email: true,
+        ..RenderMode::default()
+    };
+    render_server_side(react, react_is, &updater, jsx, mode, options).map(|result| result.html)
+}
+
+/// Same as `renderToStaticMarkup`, but `<title>`, `<meta>`, `<base>` and
+/// stylesheet or preload `<link>`s are moved out of the markup into `head`.
+#[wasm_bindgen(js_name = renderToStaticMarkupWithHead)]
+#[allow(dead_code)]
+pub fn render_to_static_markup_with_head(
+    react: &React,
+    react_is: &ReactIs,
+    jsx: JsValue,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult, JsValue> {
+    let updater = react_updater();
+    let mode = RenderMode {
+        is_static: true,
+        hoist_head_elements: true,
+        ..RenderMode::default()
+    };
+    render_server_side(react, react_is, &updater, jsx, mode, options)
+}
+
+/// Same as `renderToString`, but `<title>`, `<meta>`, `<base>` and
+/// stylesheet or preload `<link>`s are moved out of the markup into `head`.
+#[wasm_bindgen(js_name = renderToStringWithHead)]
+#[allow(dead_code)]
+pub fn render_to_string_with_head(
+    react: &React,
+    react_is: &ReactIs,
+    jsx: JsValue,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult, JsValue> {
+    let updater = react_updater();
+    let mode = RenderMode {
+        hoist_head_elements: true,
+        ..RenderMode::default()
+    };
+    render_server_side(react, react_is, &updater, jsx, mode, options)
+}
+
+#[wasm_bindgen]
+pub struct RenderResult {
+    html: JsString,
+    head: JsString,
+    styles: JsString,
+}
+
+#[wasm_bindgen]
+impl RenderResult {
+    #[wasm_bindgen(getter)]
+    pub fn html(&self) -> JsString {
+        self.html.clone()
     }
+
+    /// Hoisted elements, if the markup doesn't contain a `<head>` of its own.
+    #[wasm_bindgen(getter)]
+    pub fn head(&self) -> JsString {
+        self.head.clone()
+    }
+
+    /// The stylesheet for the markup, if styles have been extracted or a
+    /// stylesheet has been passed.
+    #[wasm_bindgen(getter)]
+    pub fn styles(&self) -> JsString {
+        self.styles.clone()
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct RenderMode {
+    /// Leaves out the markers that React needs for hydration.
+    pub is_static: bool,
+    /// Moves elements that belong into `<head>` into a result of their own.
+    pub hoist_head_elements: bool,
+    /// Inlines class styles and warns about CSS that email clients ignore.
+    pub is_email: bool,
 }
 
-fn render_jsx_to_string(
+pub fn render_server_side(
     react: &React,
     react_is: &ReactIs,
     updater: &JsValue,
-    mut parent: Option<HTMLElement>,
-    jsx: &Jsx,
-    mut context: Object,
-    is_static: bool,
-    is_root: bool,
-) -> Result<Option<HTMLElement>, JsValue> {
-    #[cfg(debug_assertions)]
-    web_sys::console::log_2(&"JSX".into(), &jsx);
-
-    match jsx.get_component(react, react_is, updater, &context)? {
-        ReactComponent::Class(component, _context_types, child_context_types) => {
-            #[cfg(debug_assertions)]
-            web_sys::console::log_2(&"CLASS".into(), &component);
-            if component.is_null() {
-                Ok(parent)
-            } else {
-                COMPONENT_WILL_MOUNT.with(|component_will_mount| {
-                    UNSAFE_COMPONENT_WILL_MOUNT.with(|unsafe_component_will_mount| {
-                        if js_sys::Reflect::has(&component, &component_will_mount)? {
-                            component.component_will_mount();
-                        } else if js_sys::Reflect::has(&component, &unsafe_component_will_mount)? {
-                            component.unsafe_component_will_mount();
-                        }
-                        let jsx = component.render();
-                        context = if child_context_types.is_truthy() {
-                            Object::assign(&context, &component.get_child_context())
-                        } else {
-                            context
-                        };
-                        if jsx.is_null() {
-                            Ok(parent)
-                        } else {
-                            render_jsx_to_string(
-                                react,
-                                react_is,
-                                updater,
-                                parent,
-                                jsx.unchecked_ref(),
-                                context,
-                                is_static,
-                                is_root,
-                            )
-                        }
-                    })
-                })
+    jsx: JsValue,
+    mode: RenderMode,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult, JsValue> {
+    let RenderMode {
+        is_static,
+        hoist_head_elements,
+        is_email,
+    } = mode;
+    let dispatcher = react_dispatcher(
+        options
+            .as_ref()
+            .and_then(|options| options.identifier_prefix()),
+    );
+    let bootstrap_scripts = create_bootstrap_scripts(options.as_ref())?;
+    let extract_styles = options
+        .as_ref()
+        .and_then(|options| options.extract_styles())
+        .unwrap_or(false)
+        && !is_email;
+    let format = create_format(options.as_ref());
+    // XML is never hydrated, so it gets no markers for React.
+    let is_static = is_static || format.xml;
+    let mut renderer = ServerRenderer {
+        react,
+        react_is,
+        updater,
+        dispatcher: &dispatcher,
+        is_static,
+        is_email,
+        is_ascii: format.ascii,
+        tree_context: TreeContext::default(),
+        namespace: Namespace::default(),
+        writer: HTMLWriter::new(!is_static, format.xml),
+        component_stack: Vec::new(),
+        bootstrap_scripts,
+        head_elements: if hoist_head_elements {
+            Some(HeadElements::default())
+        } else {
+            None
+        },
+        extracted_styles: if extract_styles {
+            Some(ExtractedStyles::default())
+        } else {
+            None
+        },
+        select_value: None,
+    };
+    let previous_resources = Resources::start();
+    dispatcher.install(react);
+    // The children of a fragment at the top level are rendered as if they
+    // were passed directly, so they are all marked as React root.
+    let res = if react_is.is_fragment(&jsx) {
+        match jsx
+            .unchecked_ref::<Jsx>()
+            .props()
+            .unchecked_ref::<JsxProps>()
+            .children()
+        {
+            Some(children) => renderer.render_node(children.into(), Object::new(), true),
+            None => Ok(()),
+        }
+    } else {
+        renderer.render_node(jsx, Object::new(), true)
+    };
+    dispatcher.uninstall(react);
+    let resources = Resources::finish(previous_resources);
+    res?;
+
+    // Without a document, the bootstrap scripts are added after the markup.
+    renderer.write_bootstrap_scripts();
+    let mut values = renderer.writer.finish();
+    if is_email {
+        add_table_defaults(&mut values);
+    }
+    let is_document = matches!(
+        values.first(),
+        Some(HTMLValue::Element(element)) if element.tag == "html"
+    );
+    let mut head_elements = renderer.head_elements;
+    let mut first_head_elements = head_elements
+        .as_mut()
+        .map(HeadElements::take_first)
+        .unwrap_or_default();
+    let mut head = head_elements
+        .map(HeadElements::into_values)
+        .unwrap_or_default();
+    // Only the rules of the stylesheet that apply to the markup are needed
+    // for the first paint, extracted styles come last so that they win.
+    let mut styles = match options.as_ref().and_then(|options| options.stylesheet()) {
+        Some(stylesheet) => {
+            let stylesheet = Stylesheet::parse(&stylesheet);
+            if is_email {
+                inline_class_styles(&mut values, &stylesheet, &|err: String| {
+                    web_sys::console::error_1(&err.into())
+                });
             }
+            let mut elements = Vec::new();
+            RenderedElement::collect(&values, &mut elements);
+            stylesheet.critical_css(&elements, is_email)
         }
-        ReactComponent::Functional(function) => {
-            #[cfg(debug_assertions)]
-            web_sys::console::log_3(&"FUNCTIONAL".into(), &function, &jsx.props());
-            let jsx = function
-                .get_type()
-                .unchecked_ref::<js_sys::Function>()
-                .call2(&function.get_type(), &jsx.props(), &context)
-                .expect("Functional Component initialization failed");
-            if jsx.is_null() {
-                Ok(parent)
-            } else {
-                render_jsx_to_string(
-                    react,
-                    react_is,
-                    updater,
-                    parent,
-                    jsx.unchecked_ref(),
-                    context,
-                    is_static,
-                    is_root,
-                )
+        None => String::new(),
+    };
+    if let Some(extracted_styles) = renderer.extracted_styles {
+        styles.push_str(&extracted_styles.to_css());
+    }
+    // Resources are loaded ahead of the markup that uses them.
+    let mut resources = resources.into_values();
+    // Without a separate result for `<head>`, the styles are written into a
+    // `<style>` element, so that the markup looks the same.
+    if !hoist_head_elements && !styles.is_empty() {
+        let style = create_style_element(&styles, options.as_ref(), &format);
+        resources.insert(0, HTMLValue::Element(style));
+    }
+    // Nothing may come before `<html>`, so a document gets a `<head>` for
+    // the resources.
+    if !hoist_head_elements && !resources.is_empty() {
+        insert_head(&mut values);
+    }
+    if let Some(head_element) = find_head(&mut values) {
+        first_head_elements.append(&mut resources);
+        first_head_elements.append(&mut head_element.children);
+        first_head_elements.append(&mut head);
+        head_element.children = first_head_elements;
+    } else if hoist_head_elements {
+        first_head_elements.append(&mut resources);
+        first_head_elements.append(&mut head);
+        head = first_head_elements;
+    } else {
+        resources.append(&mut values);
+        values = resources;
+    }
+    if format.xml {
+        declare_namespaces(&mut values, Namespace::default(), None, false);
+        declare_namespaces(&mut head, Namespace::default(), None, false);
+    }
+    let html = render_values(&values, &format);
+    // A document needs a doctype, so that browsers don't use quirks mode.
+    let html = match (is_document, format.pretty) {
+        (false, _) => html,
+        (true, None) => DOCTYPE.with(|doctype| doctype.concat(&html)),
+        (true, Some(_)) => {
+            DOCTYPE.with(|doctype| NEWLINE.with(|newline| doctype.concat(newline).concat(&html)))
+        }
+    };
+    Ok(RenderResult {
+        html,
+        head: render_values(&head, &format),
+        styles: if hoist_head_elements {
+            styles.into()
+        } else {
+            JsString::from("")
+        },
+    })
+}
+
+/// Declares the namespaces for XML, which doesn't know that HTML elements
+/// belong to XHTML, or that `svg` and `math` start a namespace of their own.
+/// `context` is the namespace new elements are created in, while `scope` is
+/// the one that has been declared by an ancestor, if any.
+fn declare_namespaces(
+    values: &mut [HTMLValue],
+    context: Namespace,
+    scope: Option<Namespace>,
+    declares_xlink: bool,
+) {
+    for value in values {
+        if let HTMLValue::Element(element) = value {
+            let tag = String::from(element.tag.clone());
+            let namespace = context.of_element(&tag);
+            if scope != Some(namespace) {
+                element
+                    .attributes
+                    .set_first("xmlns".into(), namespace.uri().into());
+            }
+            let uses_xlink = !declares_xlink
+                && element
+                    .attributes
+                    .iter()
+                    .any(|(name, _)| name.starts_with("xlink:", 0));
+            if uses_xlink {
+                element
+                    .attributes
+                    .set("xmlns:xlink".into(), XLINK_NAMESPACE.into());
             }
+            declare_namespaces(
+                &mut element.children,
+                namespace.of_children(&tag),
+                Some(namespace),
+                declares_xlink || uses_xlink,
+            );
         }
-        ReactComponent::Intrinsic(intrinsic) => {
-            #[cfg(debug_assertions)]
-            web_sys::console::log_2(&"INTRINSIC".into(), &intrinsic.clone().into());
-            render_intrinsic(
-                react, react_is, updater, parent, intrinsic, jsx, context, is_static, is_root,
-            )
+    }
+}
+
+/// Finds the `<head>` of a document.
+fn find_head(values: &mut [HTMLValue]) -> Option<&mut HTMLElement> {
+    find_html(values)?
+        .children
+        .iter_mut()
+        .find_map(|value| match value {
+            HTMLValue::Element(element) if element.tag == "head" => Some(element),
+            _ => None,
+        })
+}
+
+fn find_html(values: &mut [HTMLValue]) -> Option<&mut HTMLElement> {
+    values.iter_mut().find_map(|value| match value {
+        HTMLValue::Element(element) if element.tag == "html" => Some(element),
+        _ => None,
+    })
+}
+
+/// Adds an empty `<head>` as first child of `<html>`, unless it has one.
+fn insert_head(values: &mut [HTMLValue]) {
+    if find_head(values).is_some() {
+        return;
+    }
+    if let Some(html) = find_html(values) {
+        html.children.insert(
+            0,
+            HTMLValue::Element(HTMLElement {
+                tag: "head".into(),
+                attributes: Attributes::new(),
+                children: Vec::new(),
+            }),
+        );
+    }
+}
+
+struct ServerRenderer<'a> {
+    react: &'a React,
+    react_is: &'a ReactIs,
+    updater: &'a JsValue,
+    dispatcher: &'a ReactDispatcher,
+    is_static: bool,
+    /// Warns about styles that email clients don't support.
+    is_email: bool,
+    /// Leaves out attributes whose names can't be written in ASCII.
+    is_ascii: bool,
+    /// Position of the currently rendered node, used by `useId`.
+    tree_context: TreeContext,
+    /// Namespace of the parent element, to follow `svg` and `math` subtrees.
+    namespace: Namespace,
+    writer: HTMLWriter,
+    /// Elements that are currently being rendered, used for error messages.
+    component_stack: Vec<Jsx>,
+    /// Scripts that are written at the end of `<body>`.
+    bootstrap_scripts: Vec<HTMLElement>,
+    /// Elements that are moved into `<head>`, if hoisting is enabled.
+    head_elements: Option<HeadElements>,
+    /// Styles that are written as classes instead of `style` attributes, if
+    /// extraction is enabled.
+    extracted_styles: Option<ExtractedStyles>,
+    /// Value of the `<select>` that is currently being rendered, which marks
+    /// its options as selected.
+    select_value: Option<JsValue>,
+}
+
+impl<'a> ServerRenderer<'a> {
+    fn render_jsx_to_string(
+        &mut self,
+        jsx: &Jsx,
+        context: Object,
+        is_root: bool,
+    ) -> Result<(), JsValue> {
+        #[cfg(debug_assertions)]
+        web_sys::console::log_2(&"JSX".into(), jsx);
+
+        let component = jsx.get_component(self.react, self.react_is, self.updater, &context)?;
+        if let ReactComponent::Fragment(_) = component {
+            self.render_component(component, jsx, context, is_root)
+        } else {
+            self.component_stack.push(jsx.clone());
+            let res = self.render_component(component, jsx, context, is_root);
+            self.component_stack.pop();
+            res
         }
-        ReactComponent::Fragment(children) => {
-            if let Some(children) = children {
-                if let Some(children) = children.dyn_ref::<js_sys::Array>() {
-                    for child in children.values() {
-                        parent = render_jsx_to_string(
-                            react,
-                            react_is,
-                            updater,
-                            parent,
-                            child?.unchecked_ref(),
-                            context.clone(),
-                            is_static,
-                            is_root,
-                        )?;
-                    }
+    }
+
+    fn render_component(
+        &mut self,
+        component: ReactComponent,
+        jsx: &Jsx,
+        mut context: Object,
+        is_root: bool,
+    ) -> Result<(), JsValue> {
+        match component {
+            ReactComponent::Class(component, _context_types, child_context_types) => {
+                #[cfg(debug_assertions)]
+                web_sys::console::log_2(&"CLASS".into(), &component);
+                if component.is_null() {
+                    Ok(())
+                } else {
+                    COMPONENT_WILL_MOUNT.with(|component_will_mount| {
+                        UNSAFE_COMPONENT_WILL_MOUNT.with(|unsafe_component_will_mount| {
+                            if js_sys::Reflect::has(&component, component_will_mount)? {
+                                component.component_will_mount();
+                            } else if js_sys::Reflect::has(&component, unsafe_component_will_mount)?
+                            {
+                                component.unsafe_component_will_mount();
+                            }
+                            let jsx = component.render();
+                            context = if child_context_types.is_truthy() {
+                                Object::assign(&context, &component.get_child_context())
+                            } else {
+                                context
+                            };
+                            if jsx.is_null() {
+                                Ok(())
+                            } else {
+                                let is_root = is_root && self.react.is_valid_element(&jsx);
+                                self.render_node(jsx, context, is_root)
+                            }
+                        })
+                    })
+                }
+            }
+            ReactComponent::Functional(function) => {
+                #[cfg(debug_assertions)]
+                web_sys::console::log_3(&"FUNCTIONAL".into(), &function, &jsx.props());
+                self.dispatcher
+                    .prepare_to_use_hooks(&self.tree_context.tree_id());
+                let jsx = function
+                    .get_type()
+                    .unchecked_ref::<js_sys::Function>()
+                    .call2(&function.get_type(), &jsx.props(), &context)?;
+                let has_id = self.dispatcher.finish_hooks();
+                let is_root = is_root && self.react.is_valid_element(&jsx);
+                if jsx.is_null() {
+                    Ok(())
+                } else if has_id {
+                    // A component that called `useId` gets its own level in
+                    // the tree, so that the ids of its children don't collide.
+                    let prev_tree_context = self.tree_context.clone();
+                    self.tree_context = prev_tree_context.push(1, 0);
+                    let res = self.render_node(jsx, context, is_root);
+                    self.tree_context = prev_tree_context;
+                    res
                 } else {
-                    parent = render_jsx_to_string(
-                        react,
-                        react_is,
-                        updater,
-                        parent,
-                        children.unchecked_ref(),
-                        context,
-                        is_static,
-                        is_root,
-                    )?;
+                    self.render_node(jsx, context, is_root)
                 }
             }
-            Ok(parent)
+            ReactComponent::Intrinsic(intrinsic) => {
+                #[cfg(debug_assertions)]
+                web_sys::console::log_2(&"INTRINSIC".into(), &intrinsic.clone().into());
+                self.render_intrinsic(intrinsic, jsx, context, is_root)
+            }
+            ReactComponent::Fragment(children) => {
+                if let Some(children) = children {
+                    self.render_node(children.into(), context, false)?;
+                }
+                Ok(())
+            }
         }
     }
-}
 
-fn render_intrinsic(
-    react: &React,
-    react_is: &ReactIs,
-    updater: &JsValue,
-    parent: Option<HTMLElement>,
-    intrinsic: JsString,
-    jsx: &Jsx,
-    context: Object,
-    is_static: bool,
-    is_root: bool,
-) -> Result<Option<HTMLElement>, JsValue> {
-    EMPTY.with(|empty| {
-        HAS_OWN_PROPERTY.with(|has_own_property| {
-            STYLE.with(|style| {
-                CHILDREN.with(|children| {
-                    CLASS.with(|class| {
-                        CLASS_NAME.with(|class_name| {
-                            ON.with(|on| {
-                                REACT_ROOT.with(|react_root| {
-                                    if Reflect::get(&jsx.props(), has_own_property)?.is_function() {
-                                        if jsx.props().has_own_property(style) {
-                                            check_style_prop(jsx)?;
-                                        }
-                                    } else {
-                                        handle_poisoned_has_own_property(jsx);
-                                    }
-                                    let mut element = HTMLElement {
-                                        tag: intrinsic,
-                                        attributes: Object::new(),
-                                        children: Vec::new(),
-                                    };
-                                    let props = &jsx.props();
-                                    for prop in Object::keys(props).values() {
-                                        let key = prop?;
-                                        let value = Reflect::get(props, &key)?;
-                                        let attr_name: JsString = key.unchecked_into();
-                                        if &attr_name == has_own_property
-                                            || &attr_name == children
-                                            || attr_name.starts_with(on, 0)
+    fn render_intrinsic(
+        &mut self,
+        intrinsic: JsString,
+        jsx: &Jsx,
+        context: Object,
+        is_root: bool,
+    ) -> Result<(), JsValue> {
+        EMPTY.with(|empty| {
+            HAS_OWN_PROPERTY.with(|has_own_property| {
+                STYLE.with(|style| {
+                    REACT_ROOT.with(|react_root| {
+                        let tag = String::from(intrinsic.clone());
+                        self.validate_dangerous_tag(&tag)?;
+                        self.validate_document_nesting(&tag);
+                        let namespace = self.namespace.of_element(&tag);
+                        if namespace == Namespace::Html && tag.to_ascii_lowercase() != tag {
+                            self.warn(format!(
+                                "<{} /> is using incorrect casing. Use PascalCase for React \
+                                 components, or lowercase for HTML elements.",
+                                tag
+                            ));
+                        }
+                        if Reflect::get(&jsx.props(), has_own_property)?.is_function() {
+                            if jsx.props().has_own_property(style) {
+                                self.check_style_prop(jsx)?;
+                            }
+                        } else {
+                            self.handle_poisoned_has_own_property();
+                        }
+                        let mut element = HTMLElement {
+                            tag: intrinsic,
+                            attributes: Attributes::new(),
+                            children: Vec::new(),
+                        };
+                        let props = &jsx.props();
+                        let is_custom_element =
+                            is_custom_component(&tag, &Reflect::get(props, &"is".into())?);
+                        let mut extracted_class = None;
+                        for prop in Object::keys(props).values() {
+                            let key = prop?;
+                            let value = Reflect::get(props, &key)?;
+                            let attr_name: JsString = key.unchecked_into();
+                            let mut name = String::from(attr_name.clone());
+                            match tag.as_str() {
+                                "input" => {
+                                    // Uncontrolled inputs render their default as value.
+                                    if let Some(controlled) = get_controlled_prop(&name) {
+                                        let controlled_value =
+                                            Reflect::get(props, &controlled.into())?;
+                                        if !controlled_value.is_null()
+                                            && !controlled_value.is_undefined()
                                         {
-                                        } else if &attr_name == style {
-                                            add_style_to_attributes(
-                                                value,
-                                                attr_name,
-                                                &mut element,
-                                            )?;
-                                        } else {
-                                            let attr_value: Option<JsString> =
-                                                if let Some(attr_value) =
-                                                    value.dyn_ref::<JsString>()
-                                                {
-                                                    Some(attr_value.clone())
-                                                } else if let Some(attr_value) =
-                                                    value.dyn_ref::<js_sys::Number>()
-                                                {
-                                                    Some(attr_value.to_string(10)?)
-                                                } else if let Some(attr_value) =
-                                                    value.dyn_ref::<Object>()
-                                                {
-                                                    Some(attr_value.to_string())
-                                                } else {
-                                                    None
-                                                };
-                                            if let Some(attr_value) = attr_value {
-                                                if &attr_name == class_name {
-                                                    Reflect::set(
-                                                        &element.attributes,
-                                                        class,
-                                                        &attr_value,
-                                                    )?;
-                                                } else {
-                                                    Reflect::set(
-                                                        &element.attributes,
-                                                        &attr_name,
-                                                        &attr_value,
-                                                    )?;
-                                                }
-                                            }
+                                            continue;
                                         }
+                                        name = controlled.to_string();
                                     }
-                                    if !is_static && is_root {
-                                        Reflect::set(&element.attributes, react_root, empty)?;
-                                    }
-                                    let props = jsx.props();
-                                    let props = props.unchecked_ref::<JsxProps>();
-                                    #[cfg(debug_assertions)]
-                                    web_sys::console::log_2(&"PROPS".into(), &props);
-                                    let mut append_empty_comment = false;
-                                    let mut element = Some(element);
-                                    if let Some(children) = props.children() {
-                                        if let Some(children) = children.dyn_ref::<js_sys::Array>()
-                                        {
-                                            for child in children.values() {
-                                                element = render_intrinsic_to_string(
-                                                    react,
-                                                    react_is,
-                                                    updater,
-                                                    element.unwrap(),
-                                                    child?.into(),
-                                                    context.clone(),
-                                                    is_static,
-                                                    false,
-                                                    &mut append_empty_comment,
-                                                )?;
-                                            }
-                                        } else {
-                                            element = render_intrinsic_to_string(
-                                                react,
-                                                react_is,
-                                                updater,
-                                                element.unwrap(),
-                                                children,
-                                                context,
-                                                is_static,
-                                                false,
-                                                &mut false,
-                                            )?;
-                                        }
+                                }
+                                // The value of a textarea is its content and
+                                // the value of a select selects its options.
+                                "textarea" | "select"
+                                    if name == "value" || name == "defaultValue" =>
+                                {
+                                    continue;
+                                }
+                                _ => {}
+                            }
+                            let info = get_property_info(&name);
+                            if &attr_name == style {
+                                if value.is_null() || value.is_undefined() {
+                                    continue;
+                                }
+                                let style = style_to_css(value, |err| self.warn(err))?;
+                                if self.is_email {
+                                    check_email_css(&style.css, &|err| self.warn(err));
+                                }
+                                match self.extracted_styles.as_mut() {
+                                    _ if style.css.is_empty() => {}
+                                    Some(styles) if style.is_extractable => {
+                                        extracted_class = Some(styles.class_name(style.css))
                                     }
-                                    if let Some(mut parent) = parent {
-                                        parent.children.push(HTMLValue::Element(element.unwrap()));
-                                        Ok(Some(parent))
-                                    } else {
-                                        Ok(element)
+                                    _ => {
+                                        element
+                                            .attributes
+                                            .set(attr_name, style.css.as_str().into());
                                     }
-                                })
-                            })
-                        })
+                                }
+                            } else if &attr_name == has_own_property
+                                || name == "key"
+                                || name == "ref"
+                            {
+                            } else if is_custom_element {
+                                set_custom_attribute(&mut element.attributes, &name, &value)?;
+                            } else if let Some(PropertyType::Reserved) =
+                                info.as_ref().map(|info| info.property_type)
+                            {
+                                if name == "innerHTML" {
+                                    self.warn(
+                                        "Directly setting property `innerHTML` is not permitted. \
+                                         For more information, lookup documentation on \
+                                         `dangerouslySetInnerHTML`."
+                                            .to_string(),
+                                    );
+                                }
+                            } else if get_registration(&name).is_some() {
+                                self.check_event_listener(&name, &value);
+                            } else if self.is_unknown_event_handler(&name, &value) {
+                            } else if should_remove_attribute(&name, &value, info.as_ref()) {
+                                self.warn_removed_attribute(&tag, &name, &value);
+                            } else if let Some(info) = info {
+                                self.check_attribute_value(&name, &value);
+                                if is_boolean_attribute(&value, &info) {
+                                    element.attributes.set_boolean(info.attribute_name.into());
+                                } else {
+                                    element
+                                        .attributes
+                                        .set(info.attribute_name.into(), attribute_value(&value)?);
+                                }
+                            } else if is_attribute_name_safe(&name) {
+                                self.check_attribute_value(&name, &value);
+                                element.attributes.set(attr_name, attribute_value(&value)?);
+                            }
+                        }
+                        if self.is_ascii {
+                            self.remove_non_ascii_attributes(&tag, &mut element.attributes);
+                        }
+                        if let Some(class_name) = extracted_class {
+                            add_class_name(&mut element.attributes, &class_name);
+                        }
+                        let hoist = self.head_elements.is_some()
+                            && namespace == Namespace::Html
+                            && !self.writer.is_inside("noscript")
+                            && HeadElements::is_hoistable(&tag, props)?;
+                        // A document is hydrated as a whole, so `<html>` is
+                        // not marked as React root.
+                        if !self.is_static && is_root && !hoist && tag != "html" {
+                            element.attributes.set(react_root.clone(), empty.clone());
+                        }
+                        let props = jsx.props();
+                        let props = props.unchecked_ref::<JsxProps>();
+                        if tag == "option" {
+                            if let Some(select_value) = self.select_value.as_ref() {
+                                element.attributes.remove("selected");
+                                if is_option_selected(select_value, props)? {
+                                    element.attributes.set_boolean("selected".into());
+                                }
+                            }
+                        }
+                        #[cfg(debug_assertions)]
+                        web_sys::console::log_2(&"PROPS".into(), props);
+                        if element.is_self_closing() {
+                            self.check_void_element(&element, props)?;
+                        }
+                        let form_value = match tag.as_str() {
+                            "textarea" | "select" => get_form_value(props)?,
+                            _ => None,
+                        };
+                        self.writer.open_element(element);
+                        if let (true, Some(value)) = (tag == "textarea", form_value.as_ref()) {
+                            self.writer.write_text(attribute_value(value)?);
+                        } else if let Some(inner_html) = props.dangerously_set_inner_html() {
+                            self.render_inner_html(inner_html, props)?;
+                        } else if let Some(children) = props.children() {
+                            self.check_raw_text_children(&tag);
+                            let prev_namespace = self.namespace;
+                            self.namespace = namespace.of_children(&tag);
+                            let prev_select_value = if tag == "select" {
+                                Some(std::mem::replace(&mut self.select_value, form_value))
+                            } else {
+                                None
+                            };
+                            let res = self.render_node(children.into(), context, false);
+                            if let Some(prev_select_value) = prev_select_value {
+                                self.select_value = prev_select_value;
+                            }
+                            self.namespace = prev_namespace;
+                            res?;
+                        }
+                        // The scripts go at the end of `<body>`, or of `<html>`
+                        // for a document without body.
+                        if (tag == "body" || tag == "html") && namespace == Namespace::Html {
+                            self.write_bootstrap_scripts();
+                        }
+                        match (hoist, self.head_elements.as_mut()) {
+                            (true, Some(head_elements)) => {
+                                if let Some(element) = self.writer.take_element() {
+                                    head_elements.insert(element, props.unchecked_ref())?;
+                                }
+                            }
+                            _ => self.writer.close_element(),
+                        }
+                        Ok(())
                     })
                 })
             })
         })
-    })
-}
+    }
+
+    fn render_inner_html(&mut self, inner_html: Object, props: &JsxProps) -> Result<(), JsValue> {
+        if props.children().is_some() {
+            let mut err =
+                "Can only set one of `children` or `props.dangerouslySetInnerHTML`.".to_string();
+            self.add_component_stack(&mut err);
+            return Err(js_sys::Error::new(&err).into());
+        }
+        if !Reflect::has(&inner_html, &"__html".into())? {
+            let mut err = "`props.dangerouslySetInnerHTML` must be in the form `{__html: ...}`. \
+                           Please visit https://fb.me/react-invariant-dangerously-set-inner-html \
+                           for more information."
+                .to_string();
+            self.add_component_stack(&mut err);
+            return Err(js_sys::Error::new(&err).into());
+        }
+        let html = Reflect::get(&inner_html, &"__html".into())?;
+        if !html.is_null() && !html.is_undefined() {
+            self.writer.write_raw(attribute_value(&html)?);
+        }
+        Ok(())
+    }
 
-fn check_style_prop(jsx: &Jsx) -> Result<(), JsValue> {
-    STYLE.with(|style| {
-        let style = Reflect::get(&jsx.props(), style)?;
-        if style.is_object() {
+    /// `<html>` can only be the root of the document, and `<head>` and
+    /// `<body>` can only be its children.
+    fn validate_document_nesting(&self, tag: &str) {
+        let parent_tag = self.writer.parent_tag();
+        let is_valid = match tag {
+            "html" => parent_tag.is_none(),
+            "head" | "body" => parent_tag.is_none_or(|parent_tag| parent_tag == "html"),
+            _ => true,
+        };
+        if !is_valid {
+            if let Some(parent_tag) = self.writer.parent_tag() {
+                self.warn(format!(
+                    "validateDOMNesting(...): <{}> cannot appear as a child of <{}>.",
+                    tag, parent_tag
+                ));
+            }
+        }
+    }
+
+    fn write_bootstrap_scripts(&mut self) {
+        for script in std::mem::take(&mut self.bootstrap_scripts) {
+            self.writer.open_element(script);
+            self.writer.close_element();
+        }
+    }
+
+    /// Children of `script` and `style` are written without escaping, except
+    /// for a closing tag, which is rarely what was intended.
+    fn check_raw_text_children(&self, tag: &str) {
+        if matches!(
+            ContentModel::of_element(tag),
+            ContentModel::Script | ContentModel::Style
+        ) {
+            self.warn(format!(
+                "The content of <{0}> is not escaped like text. Pass it in \
+                 `dangerouslySetInnerHTML` instead of `children`, e.g. \
+                 <{0} dangerouslySetInnerHTML={{{{__html: content}}}} />.",
+                tag
+            ));
+        }
+    }
+
+    /// Only allow tag names that can't be used to inject markup.
+    fn validate_dangerous_tag(&self, tag: &str) -> Result<(), JsValue> {
+        if is_valid_tag(tag) {
             Ok(())
         } else {
-            let mut err =
-                "The `style` prop expects a mapping from style properties to values, not \
-                 a string. For example, style={{marginRight: spacing + 'em'}} when using JSX."
-                    .to_string();
-            jsx.add_component_stack(&mut err);
+            let mut err = format!("Invalid tag: {}", tag);
+            self.add_component_stack(&mut err);
             Err(js_sys::Error::new(&err).into())
         }
-    })
+    }
+
+    fn check_void_element(&self, element: &HTMLElement, props: &JsxProps) -> Result<(), JsValue> {
+        if props.children().is_some() || props.dangerously_set_inner_html().is_some() {
+            let mut err = format!(
+                "{} is a void element tag and must neither have `children` nor use \
+                 `dangerouslySetInnerHTML`.",
+                String::from(element.tag.clone())
+            );
+            self.add_component_stack(&mut err);
+            Err(js_sys::Error::new(&err).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_style_prop(&self, jsx: &Jsx) -> Result<(), JsValue> {
+        STYLE.with(|style| {
+            let style = Reflect::get(&jsx.props(), style)?;
+            if style.is_object() {
+                Ok(())
+            } else {
+                let mut err =
+                    "The `style` prop expects a mapping from style properties to values, not \
+                     a string. For example, style={{marginRight: spacing + 'em'}} when using JSX."
+                        .to_string();
+                self.add_component_stack(&mut err);
+                Err(js_sys::Error::new(&err).into())
+            }
+        })
+    }
+
+    fn check_event_listener(&self, name: &str, listener: &JsValue) {
+        if listener.is_null() || listener.is_undefined() || listener.is_function() {
+            return;
+        }
+        let err = if listener.as_bool() == Some(false) {
+            format!(
+                "Expected `{0}` listener to be a function, instead got `false`.\n\n\
+                 If you used to conditionally omit it with {0}={{condition && value}}, \
+                 pass {0}={{condition ? value : undefined}} instead.",
+                name
+            )
+        } else {
+            format!(
+                "Expected `{}` listener to be a function, instead got a value of `{}` type.",
+                name,
+                type_of(listener)
+            )
+        };
+        self.warn(err);
+    }
+
+    /// Props that look like a misspelled or unknown event listener, the
+    /// event handler attributes of the browser and functions starting with
+    /// `on` are ignored. Other props starting with `on`, like `one` or
+    /// `only`, are regular attributes.
+    fn is_unknown_event_handler(&self, name: &str, value: &JsValue) -> bool {
+        let err = if let Some(registration_name) = get_possible_registration_name(name) {
+            format!(
+                "Invalid event handler property `{}`. Did you mean `{}`?",
+                name, registration_name
+            )
+        } else if is_event_handler_name(name) || (name.starts_with("on") && value.is_function()) {
+            format!(
+                "Unknown event handler property `{}`. It will be ignored.",
+                name
+            )
+        } else {
+            return false;
+        };
+        self.warn(err);
+        true
+    }
+
+    /// Attribute names can't contain character references, so in ASCII output
+    /// the attributes with other characters in their name are left out.
+    fn remove_non_ascii_attributes(&self, tag: &str, attributes: &mut Attributes) {
+        attributes.retain(|name| {
+            if is_ascii(name) {
+                return true;
+            }
+            self.warn(format!(
+                "The attribute `{}` on <{}> is left out, because its name can't be \
+                 written as ASCII.",
+                String::from(name.clone()),
+                tag
+            ));
+            false
+        });
+    }
+
+    fn warn_removed_attribute(&self, tag: &str, name: &str, value: &JsValue) {
+        if value.is_function() || value.is_symbol() {
+            self.warn(format!(
+                "Invalid value for prop `{}` on <{}> tag. Either remove it from the element, \
+                 or pass a string or number value to keep it in the DOM. For details, see \
+                 https://fb.me/react-attribute-behavior",
+                name, tag
+            ));
+        } else if let Some(value) = value.as_bool() {
+            if !get_property_info(name).is_some_and(|info| info.accepts_booleans()) {
+                let mut err = format!(
+                    "Received `{1}` for a non-boolean attribute `{0}`.\n\n\
+                     If you want to write it to the DOM, pass a string instead: \
+                     {0}=\"{1}\" or {0}={{value.toString()}}.",
+                    name, value
+                );
+                if !value {
+                    err.push_str(&format!(
+                        "\n\nIf you used to conditionally omit it with {0}={{condition && value}}, \
+                         pass {0}={{condition ? value : undefined}} instead.",
+                        name
+                    ));
+                }
+                self.warn(err);
+            }
+        }
+    }
+
+    fn check_attribute_value(&self, name: &str, value: &JsValue) {
+        if value.as_f64().is_some_and(f64::is_nan) {
+            self.warn(format!(
+                "Received NaN for the `{}` attribute. If this is expected, cast the value to a \
+                 string.",
+                name
+            ));
+        }
+    }
+
+    fn warn(&self, mut err: String) {
+        self.add_component_stack(&mut err);
+        web_sys::console::error_1(&err.into());
+    }
+
+    fn handle_poisoned_has_own_property(&self) {
+        self.warn("React does not recognize the `hasOwnProperty` prop".to_string());
+    }
+
+    fn add_component_stack(&self, err: &mut String) {
+        for jsx in self.component_stack.iter().rev() {
+            jsx.add_component_stack(err);
+        }
+    }
+
+    /// Renders a list of children, that has been created from an array or
+    /// any other iterable. Every child gets its own position in the tree
+    /// context.
+    fn render_children(
+        &mut self,
+        children: Vec<JsValue>,
+        context: Object,
+        is_root: bool,
+    ) -> Result<(), JsValue> {
+        let prev_tree_context = self.tree_context.clone();
+        let total_children = children.len() as u32;
+        for (index, child) in children.into_iter().enumerate() {
+            self.tree_context = prev_tree_context.push(total_children, index as u32);
+            self.render_node(child, context.clone(), is_root)?;
+        }
+        self.tree_context = prev_tree_context;
+        Ok(())
+    }
+
+    fn render_node(
+        &mut self,
+        node: JsValue,
+        context: Object,
+        is_root: bool,
+    ) -> Result<(), JsValue> {
+        #[cfg(debug_assertions)]
+        web_sys::console::log_2(&"RENDER_NODE".into(), &node);
+        if node.is_null() || node.is_undefined() || node.as_bool().is_some() {
+        } else if let Some(js_string) = node.dyn_ref::<JsString>() {
+            self.writer.write_text(js_string.clone());
+        } else if let Some(js_number) = node.dyn_ref::<js_sys::Number>() {
+            self.writer.write_text(js_number.to_string(10)?);
+        } else if is_big_int(&node) {
+            self.writer
+                .write_text(node.unchecked_ref::<Object>().to_string());
+        } else if self.react.is_valid_element(&node) {
+            self.render_jsx_to_string(node.unchecked_ref(), context, is_root)?;
+        } else if node.is_function() {
+            self.warn(
+                "Functions are not valid as a React child. This may happen if you return a \
+                 Component instead of <Component /> from render. Or maybe you meant to call \
+                 this function rather than return it."
+                    .to_string(),
+            );
+        } else if node.is_symbol() {
+            let mut err = "Symbols are not valid as a React child.\n  ".to_string();
+            err.push_str(&String::from(
+                node.unchecked_ref::<js_sys::Symbol>().to_string(),
+            ));
+            self.warn(err);
+        } else if let Some(children) = iterate_children(&node)? {
+            self.render_children(children, context, is_root)?;
+        } else {
+            return Err(objects_are_not_valid(&node.unchecked_into()));
+        }
+        Ok(())
+    }
+}
+
+/// Collects the children of an array or any other iterable object, like a
+/// `Set` or a generator. Nested iterables are flattened by `render_node`.
+fn iterate_children(node: &JsValue) -> Result<Option<Vec<JsValue>>, JsValue> {
+    if let Some(children) = node.dyn_ref::<js_sys::Array>() {
+        Ok(Some(
+            children
+                .values()
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    } else if node.is_object() {
+        match js_sys::try_iter(node)? {
+            Some(children) => Ok(Some(children.collect::<Result<Vec<_>, _>>()?)),
+            None => Ok(None),
+        }
+    } else {
+        Ok(None)
+    }
 }
 
-fn handle_poisoned_has_own_property(jsx: &Jsx) {
-    let mut err = "React does not recognize the `hasOwnProperty` prop".to_string();
-    jsx.add_component_stack(&mut err);
-    web_sys::console::error_1(&err.into());
+fn objects_are_not_valid(obj: &Object) -> JsValue {
+    let mut err = "Objects are not valid as a React child (found: object with keys {".to_string();
+    Object::keys(obj).for_each(&mut |key, index, _| {
+        if index != 0 {
+            err.push_str(", ");
+        }
+        let key: JsString = key.into();
+        let key: String = key.into();
+        err.push_str(&key);
+    });
+    err.push_str("}). If you meant to render a collection of children, use an array instead.");
+    js_sys::Error::new(&err).into()
 }
 
-fn render_intrinsic_to_string(
-    react: &React,
-    react_is: &ReactIs,
-    updater: &JsValue,
-    mut parent: HTMLElement,
-    js_val: Object,
-    context: Object,
-    is_static: bool,
-    is_root: bool,
-    append_empty_comment: &mut bool,
-) -> Result<Option<HTMLElement>, JsValue> {
-    #[cfg(debug_assertions)]
-    web_sys::console::log_2(&"RENDER_INTRINSIC".into(), &js_val);
-    match js_val.dyn_ref::<JsString>() {
-        Some(js_string) => {
-            render_text(js_string, &mut parent, append_empty_comment);
+/// Same as React's `VALID_TAG_REGEX`: `/^[a-zA-Z][a-zA-Z:_\.\-\d]*$/`
+fn is_valid_tag(tag: &str) -> bool {
+    let mut chars = tag.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == ':' || c == '_' || c == '.' || c == '-')
+        }
+        _ => false,
+    }
+}
+
+/// Same as JavaScript's `typeof` operator.
+fn type_of(value: &JsValue) -> &'static str {
+    if value.is_undefined() {
+        "undefined"
+    } else if value.as_bool().is_some() {
+        "boolean"
+    } else if value.as_f64().is_some() {
+        "number"
+    } else if value.is_string() {
+        "string"
+    } else if value.is_symbol() {
+        "symbol"
+    } else if is_big_int(value) {
+        "bigint"
+    } else if value.is_function() {
+        "function"
+    } else {
+        "object"
+    }
+}
+
+/// Inputs accept `defaultValue` and `defaultChecked` instead of `value` and
+/// `checked`.
+fn get_controlled_prop(name: &str) -> Option<&'static str> {
+    match name {
+        "defaultValue" => Some("value"),
+        "defaultChecked" => Some("checked"),
+        _ => None,
+    }
+}
+
+/// The `value` of a textarea or select, or its `defaultValue` if it is
+/// uncontrolled.
+fn get_form_value(props: &JsxProps) -> Result<Option<JsValue>, JsValue> {
+    for name in &["value", "defaultValue"] {
+        let value = Reflect::get(props, &(*name).into())?;
+        if !value.is_null() && !value.is_undefined() {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+/// An option is selected if its `value`, or otherwise its text, equals the
+/// value of the select, or one of its values if it is `multiple`.
+fn is_option_selected(select_value: &JsValue, props: &JsxProps) -> Result<bool, JsValue> {
+    let value = Reflect::get(props, &"value".into())?;
+    let value = if value.is_null() || value.is_undefined() {
+        let mut text = String::new();
+        if let Some(children) = props.children() {
+            write_option_text(&children, &mut text)?;
         }
-        None => match js_val.dyn_ref::<js_sys::Number>() {
-            Some(js_number) => {
-                render_text(&js_number.to_string(10)?, &mut parent, append_empty_comment);
+        text
+    } else {
+        String::from(attribute_value(&value)?)
+    };
+    if let Some(values) = select_value.dyn_ref::<Array>() {
+        for selected in values.iter() {
+            if attribute_value(&selected)? == value {
+                return Ok(true);
             }
-            None => {
-                if js_val.is_truthy() {
-                    *append_empty_comment = false;
-                    parent = render_jsx_to_string(
-                        react,
-                        react_is,
-                        updater,
-                        Some(parent),
-                        js_val.unchecked_ref(),
-                        context,
-                        is_static,
-                        is_root,
-                    )?
-                    .unwrap();
-                }
+        }
+        Ok(false)
+    } else {
+        Ok(attribute_value(select_value)? == value)
+    }
+}
+
+/// The text of an option's children, which React flattens to a string.
+fn write_option_text(children: &JsValue, text: &mut String) -> Result<(), JsValue> {
+    if let Some(children) = children.dyn_ref::<Array>() {
+        for child in children.iter() {
+            write_option_text(&child, text)?;
+        }
+    } else if children.is_string() || children.dyn_ref::<js_sys::Number>().is_some() {
+        text.push_str(&String::from(attribute_value(children)?));
+    }
+    Ok(())
+}
+
+/// Creates the `<script>` elements for `bootstrapScripts` and
+/// `bootstrapModules`, which load the client code.
+fn create_bootstrap_scripts(options: Option<&RenderOptions>) -> Result<Vec<HTMLElement>, JsValue> {
+    let mut scripts = Vec::new();
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(scripts),
+    };
+    let nonce = options.nonce();
+    let sources = options
+        .bootstrap_scripts()
+        .into_iter()
+        .flat_map(|sources| sources.values().into_iter())
+        .map(|src| (false, src))
+        .chain(
+            options
+                .bootstrap_modules()
+                .into_iter()
+                .flat_map(|sources| sources.values().into_iter())
+                .map(|src| (true, src)),
+        );
+    for (is_module, src) in sources {
+        let mut attributes = Attributes::new();
+        if is_module {
+            attributes.set("type".into(), "module".into());
+        }
+        attributes.set("src".into(), attribute_value(&src?)?);
+        if let Some(nonce) = &nonce {
+            attributes.set("nonce".into(), nonce.as_str().into());
+        }
+        attributes.set_boolean("async".into());
+        scripts.push(HTMLElement {
+            tag: "script".into(),
+            attributes,
+            children: Vec::new(),
+        });
+    }
+    Ok(scripts)
+}
+
+fn create_format(options: Option<&RenderOptions>) -> Format {
+    let pretty = options
+        .filter(|options| options.pretty().unwrap_or(false))
+        .map(|options| {
+            let mut pretty = Pretty::default();
+            if let Some(width) = options.print_width() {
+                pretty.width = width as usize;
             }
-        },
+            pretty
+        });
+    Format {
+        pretty,
+        sort_attributes: options
+            .and_then(|options| options.sort_attributes())
+            .unwrap_or(false),
+        xml: options.and_then(|options| options.xml()).unwrap_or(false),
+        ascii: options.and_then(|options| options.ascii()).unwrap_or(false),
+    }
+}
+
+/// Creates the `<style>` element for extracted styles.
+fn create_style_element(
+    styles: &str,
+    options: Option<&RenderOptions>,
+    format: &Format,
+) -> HTMLElement {
+    let mut attributes = Attributes::new();
+    if let Some(nonce) = options.and_then(|options| options.nonce()) {
+        attributes.set("nonce".into(), nonce.as_str().into());
+    }
+    HTMLElement {
+        tag: "style".into(),
+        attributes,
+        children: vec![if format.xml {
+            HTMLValue::Text(styles.into())
+        } else {
+            HTMLValue::Raw(escape_style_content(&styles.into()))
+        }],
+    }
+}
+
+/// Adds a class to the ones from the `className` prop, if there are any.
+fn add_class_name(attributes: &mut Attributes, class_name: &str) {
+    let class = match attributes.get("class").map(String::from) {
+        Some(class) if !class.is_empty() => format!("{} {}", class, class_name),
+        _ => class_name.to_string(),
     };
-    Ok(Some(parent))
+    attributes.set("class".into(), class.as_str().into());
 }
 
-fn render_text(js_string: &JsString, parent: &mut HTMLElement, append_empty_comment: &mut bool) {
-    if *append_empty_comment {
-        render_empty_comment(parent);
+/// Custom elements get their props as attributes without a lookup in the
+/// property table. Like React 19, `true` is written as an empty attribute and
+/// values that can't be serialised, including `false`, are left out.
+fn set_custom_attribute(
+    attributes: &mut Attributes,
+    name: &str,
+    value: &JsValue,
+) -> Result<(), JsValue> {
+    if is_reserved_custom_component_prop(name)
+        || value.is_null()
+        || value.is_undefined()
+        || value.is_function()
+        || value.is_symbol()
+        || value.is_object()
+        || value.as_bool() == Some(false)
+    {
+        return Ok(());
+    }
+    let attribute_name = if name == "className" { "class" } else { name };
+    if is_attribute_name_safe(attribute_name) {
+        if value.as_bool() == Some(true) {
+            attributes.set_boolean(attribute_name.into());
+        } else {
+            attributes.set(attribute_name.into(), attribute_value(value)?);
+        }
     }
-    render_text_component(parent, js_string.clone());
-    *append_empty_comment = true;
+    Ok(())
 }
 
-fn render_empty_comment(parent: &mut HTMLElement) {
-    parent.children.push(HTMLValue::Comment);
+/// Boolean attributes are written without a value, which is `""` in HTML.
+fn is_boolean_attribute(value: &JsValue, info: &PropertyInfo) -> bool {
+    info.property_type == PropertyType::Boolean
+        || (info.property_type == PropertyType::OverloadedBoolean && value.as_bool() == Some(true))
 }
 
-fn render_text_component(parent: &mut HTMLElement, s: JsString) {
-    parent.children.push(HTMLValue::Text(s));
+fn attribute_value(value: &JsValue) -> Result<JsString, JsValue> {
+    if let Some(value) = value.dyn_ref::<JsString>() {
+        Ok(value.clone())
+    } else if let Some(value) = value.as_bool() {
+        Ok(if value { "true" } else { "false" }.into())
+    } else if let Some(value) = value.dyn_ref::<js_sys::Number>() {
+        value.to_string(10)
+    } else {
+        Ok(value.unchecked_ref::<Object>().to_string())
+    }
 }
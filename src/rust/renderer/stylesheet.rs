@@ -0,0 +1,428 @@
+use crate::html::{HTMLElement, HTMLValue};
+
+/// A parsed CSS stylesheet. Only the structure that is needed to pick rules
+/// for the rendered markup is parsed, declarations are kept as written.
+pub struct Stylesheet {
+    rules: Vec<Rule>,
+}
+
+enum Rule {
+    Style {
+        selectors: Vec<String>,
+        declarations: String,
+    },
+    /// `@media`, `@supports` and other at-rules that contain style rules.
+    Conditional { prelude: String, rules: Vec<Rule> },
+    /// At-rules like `@font-face`, `@keyframes` or `@import`, which are
+    /// kept as they are.
+    Other(String),
+}
+
+/// The tag, id and classes of a rendered element, which is all that is
+/// needed for selector matching.
+pub struct RenderedElement {
+    tag: String,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl RenderedElement {
+    pub fn new(element: &HTMLElement) -> RenderedElement {
+        let attribute = |name: &str| element.attributes.get(name).map(String::from);
+        RenderedElement {
+            tag: String::from(element.tag.clone()).to_ascii_lowercase(),
+            id: attribute("id"),
+            classes: attribute("class")
+                .map(|class| class.split_ascii_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Collects all elements of the rendered markup, including the ones in
+    /// markup from `dangerouslySetInnerHTML`.
+    pub fn collect(values: &[HTMLValue], elements: &mut Vec<RenderedElement>) {
+        for value in values {
+            match value {
+                HTMLValue::Element(element) => {
+                    elements.push(RenderedElement::new(element));
+                    RenderedElement::collect(&element.children, elements);
+                }
+                HTMLValue::Raw(html) => {
+                    RenderedElement::collect_raw(&String::from(html.clone()), elements)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Collects the elements of the start tags in raw markup. Anything that
+    /// looks like a start tag is taken, so rules are rather kept than missed.
+    fn collect_raw(html: &str, elements: &mut Vec<RenderedElement>) {
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+                continue;
+            }
+            if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            let end = rest
+                .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+                .unwrap_or(rest.len());
+            let mut element = RenderedElement {
+                tag: rest[..end].to_ascii_lowercase(),
+                id: None,
+                classes: Vec::new(),
+            };
+            rest = &rest[end..];
+            loop {
+                rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+                if rest.is_empty() || rest.starts_with('>') {
+                    break;
+                }
+                let end = rest
+                    .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '/' | '>'))
+                    .unwrap_or(rest.len());
+                let name = rest[..end].to_ascii_lowercase();
+                rest = rest[end..].trim_start();
+                let value = match rest.strip_prefix('=') {
+                    Some(value) => {
+                        let (value, next) = split_attribute_value(value.trim_start());
+                        rest = next;
+                        value
+                    }
+                    None => "",
+                };
+                match name.as_str() {
+                    "id" => element.id = Some(value.to_string()),
+                    "class" => {
+                        element.classes = value.split_ascii_whitespace().map(String::from).collect()
+                    }
+                    _ => {}
+                }
+            }
+            elements.push(element);
+        }
+    }
+}
+
+/// Splits a quoted or unquoted attribute value from the rest of the tag.
+fn split_attribute_value(html: &str) -> (&str, &str) {
+    match html.chars().next() {
+        Some(quote) if quote == '"' || quote == '\'' => {
+            let value = &html[1..];
+            match value.find(quote) {
+                Some(end) => (&value[..end], &value[end + 1..]),
+                None => (value, ""),
+            }
+        }
+        _ => {
+            let end = html
+                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                .unwrap_or(html.len());
+            html.split_at(end)
+        }
+    }
+}
+
+impl Stylesheet {
+    pub fn parse(css: &str) -> Stylesheet {
+        Stylesheet {
+            rules: parse_rules(&strip_comments(css)),
+        }
+    }
+
+    /// The rules that apply to at least one of the elements, with selectors
+    /// that don't match anything removed from selector lists. Only the last
+    /// compound selector is matched, so rules are rather kept than missed.
+    /// Selectors of a single class are left out if `inline_classes` is set,
+    /// because their declarations are written into the `style` attribute.
+    pub fn critical_css(&self, elements: &[RenderedElement], inline_classes: bool) -> String {
+        let mut css = String::new();
+        write_critical_rules(&mut css, &self.rules, elements, inline_classes);
+        css
+    }
+
+    /// The declarations of the rules outside of at-rules that have a
+    /// selector of a single one of the classes, in order of the stylesheet.
+    pub fn class_declarations(&self, classes: &[String]) -> Vec<&str> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::Style {
+                    selectors,
+                    declarations,
+                } if selectors.iter().any(|selector| {
+                    class_selector(selector).is_some_and(|class| classes.contains(&class))
+                }) =>
+                {
+                    Some(declarations.as_str())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn write_critical_rules(
+    css: &mut String,
+    rules: &[Rule],
+    elements: &[RenderedElement],
+    inline_classes: bool,
+) {
+    for rule in rules {
+        match rule {
+            Rule::Style {
+                selectors,
+                declarations,
+            } => {
+                let selectors = selectors
+                    .iter()
+                    .filter(|selector| !inline_classes || class_selector(selector).is_none())
+                    .filter(|selector| matches_any(selector, elements))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                if !selectors.is_empty() {
+                    css.push_str(&selectors.join(","));
+                    css.push('{');
+                    css.push_str(declarations);
+                    css.push('}');
+                }
+            }
+            Rule::Conditional { prelude, rules } => {
+                let mut inner = String::new();
+                // Rules in at-rules can't be inlined.
+                write_critical_rules(&mut inner, rules, elements, false);
+                if !inner.is_empty() {
+                    css.push_str(prelude);
+                    css.push('{');
+                    css.push_str(&inner);
+                    css.push('}');
+                }
+            }
+            Rule::Other(rule) => css.push_str(rule),
+        }
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+fn parse_rules(css: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let mut rest = css.trim_start();
+    while !rest.is_empty() {
+        let (prelude, block, next) = match find_top_level(rest, &['{', ';']) {
+            Some((index, '{')) => {
+                let end = find_block_end(rest, index);
+                (
+                    rest[..index].trim(),
+                    Some(&rest[index + 1..end.min(rest.len())]),
+                    &rest[(end + 1).min(rest.len())..],
+                )
+            }
+            Some((index, _)) => (rest[..index].trim(), None, &rest[index + 1..]),
+            None => (rest.trim(), None, ""),
+        };
+        match block {
+            Some(block) if is_conditional(prelude) => rules.push(Rule::Conditional {
+                prelude: prelude.to_string(),
+                rules: parse_rules(block),
+            }),
+            Some(block) if prelude.starts_with('@') => {
+                rules.push(Rule::Other(format!("{}{{{}}}", prelude, block.trim())))
+            }
+            Some(block) if !prelude.is_empty() => rules.push(Rule::Style {
+                selectors: split_top_level(prelude, ',')
+                    .into_iter()
+                    .map(|selector| selector.trim().to_string())
+                    .filter(|selector| !selector.is_empty())
+                    .collect(),
+                declarations: block.trim().to_string(),
+            }),
+            None if prelude.starts_with('@') => rules.push(Rule::Other(format!("{};", prelude))),
+            _ => {}
+        }
+        rest = next.trim_start();
+    }
+    rules
+}
+
+fn is_conditional(prelude: &str) -> bool {
+    ["@media", "@supports", "@container", "@layer", "@document"]
+        .iter()
+        .any(|at_rule| prelude.starts_with(at_rule))
+}
+
+/// Finds the first of the characters outside of strings, brackets and
+/// parentheses.
+fn find_top_level(css: &str, chars: &[char]) -> Option<(usize, char)> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in css.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+        } else if c == '"' || c == '\'' {
+            quote = Some(c);
+        } else if depth == 0 && chars.contains(&c) {
+            return Some((index, c));
+        } else if c == '(' || c == '[' {
+            depth += 1;
+        } else if c == ')' || c == ']' {
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Finds the `}` that closes the block opened at `start`.
+fn find_block_end(css: &str, start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in css[start..].char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+        } else if c == '"' || c == '\'' {
+            quote = Some(c);
+        } else if c == '{' {
+            depth += 1;
+        } else if c == '}' {
+            depth -= 1;
+            if depth == 0 {
+                return start + index;
+            }
+        }
+    }
+    css.len()
+}
+
+/// Splits at the separator, except within strings, brackets and
+/// parentheses.
+pub fn split_top_level(css: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = css;
+    while let Some((index, _)) = find_top_level(rest, &[separator]) {
+        parts.push(&rest[..index]);
+        rest = &rest[index + separator.len_utf8()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// The class of a selector like `.button`, which consists of nothing else.
+fn class_selector(selector: &str) -> Option<String> {
+    let mut chars = selector.strip_prefix('.')?.chars();
+    let mut class = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => class.push(chars.next()?),
+            '.' | '#' | ':' | '[' | '*' | '>' | '+' | '~' => return None,
+            c if c.is_whitespace() => return None,
+            c => class.push(c),
+        }
+    }
+    Some(class).filter(|class| !class.is_empty())
+}
+
+fn matches_any(selector: &str, elements: &[RenderedElement]) -> bool {
+    let compound = last_compound(selector);
+    elements
+        .iter()
+        .any(|element| matches_compound(compound, element))
+}
+
+/// The compound selector after the last combinator, e.g. `a.active` for
+/// `nav > ul a.active`.
+fn last_compound(selector: &str) -> &str {
+    let mut start = 0;
+    let mut depth = 0;
+    let mut escaped = false;
+    for (index, c) in selector.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '(' || c == '[' {
+            depth += 1;
+        } else if c == ')' || c == ']' {
+            depth -= 1;
+        } else if depth == 0 && (c.is_whitespace() || matches!(c, '>' | '+' | '~')) {
+            start = index + c.len_utf8();
+        }
+    }
+    &selector[start..]
+}
+
+/// Matches the type, class and id selectors of a compound selector.
+/// Attribute selectors and pseudo-classes are assumed to match.
+fn matches_compound(compound: &str, element: &RenderedElement) -> bool {
+    let mut chars = compound.char_indices().peekable();
+    let mut depth = 0;
+    let mut kind = None;
+    let mut name = String::new();
+    let mut matches = true;
+    let mut check = |kind: Option<char>, name: &str| match kind {
+        None if !name.is_empty() && name != "*" => {
+            matches &= name.eq_ignore_ascii_case(&element.tag)
+        }
+        Some('.') => matches &= element.classes.iter().any(|class| class == name),
+        Some('#') => matches &= element.id.as_deref() == Some(name),
+        _ => {}
+    };
+    while let Some((_, c)) = chars.next() {
+        if depth > 0 {
+            if c == '(' || c == '[' {
+                depth += 1;
+            } else if c == ')' || c == ']' {
+                depth -= 1;
+            }
+            continue;
+        }
+        match c {
+            '\\' => {
+                if let Some((_, next)) = chars.next() {
+                    name.push(next);
+                }
+            }
+            '.' | '#' | ':' | '[' => {
+                check(kind, &name);
+                name.clear();
+                kind = Some(c);
+                if c == '[' {
+                    depth += 1;
+                }
+            }
+            '(' => depth += 1,
+            _ => name.push(c),
+        }
+    }
+    check(kind, &name);
+    matches
+}
@@ -0,0 +1,74 @@
+/// Position of a component in the tree, encoded the same way as React's
+/// `ReactFizzTreeContext`. Every array of children adds the index of the
+/// child (plus one) as a new set of bits in front of the id. Once the id
+/// would exceed 30 bits, the lowest bits are moved into `overflow` as a
+/// base 32 string.
+#[derive(Clone)]
+pub struct TreeContext {
+    id: u32,
+    overflow: String,
+}
+
+impl Default for TreeContext {
+    fn default() -> Self {
+        TreeContext {
+            id: 1,
+            overflow: String::new(),
+        }
+    }
+}
+
+impl TreeContext {
+    pub fn push(&self, total_children: u32, index: u32) -> TreeContext {
+        let base_length = bit_length(self.id) - 1;
+        let base_id = self.id & !(1 << base_length);
+        let slot = index + 1;
+        let length = bit_length(total_children) + base_length;
+
+        if length > 30 {
+            let number_of_overflow_bits = base_length - (base_length % 5);
+            let new_overflow_bits = (1 << number_of_overflow_bits) - 1;
+            let new_overflow = to_base_32(base_id & new_overflow_bits);
+            let rest_of_base_id = base_id >> number_of_overflow_bits;
+            let rest_of_base_length = base_length - number_of_overflow_bits;
+            let rest_of_length = bit_length(total_children) + rest_of_base_length;
+            let rest_of_new_bits = slot << rest_of_base_length;
+            let id = rest_of_new_bits | rest_of_base_id;
+            TreeContext {
+                id: (1 << rest_of_length) | id,
+                overflow: new_overflow + &self.overflow,
+            }
+        } else {
+            let new_bits = slot << base_length;
+            let id = new_bits | base_id;
+            TreeContext {
+                id: (1 << length) | id,
+                overflow: self.overflow.clone(),
+            }
+        }
+    }
+
+    pub fn tree_id(&self) -> String {
+        let id = self.id & !(1 << (bit_length(self.id) - 1));
+        to_base_32(id) + &self.overflow
+    }
+}
+
+fn bit_length(number: u32) -> u32 {
+    32 - number.leading_zeros()
+}
+
+/// Same output as JavaScript's `Number.prototype.toString(32)` for integers.
+fn to_base_32(mut number: u32) -> String {
+    const DIGITS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+    let mut res = Vec::new();
+    loop {
+        res.push(DIGITS[(number % 32) as usize]);
+        number /= 32;
+        if number == 0 {
+            break;
+        }
+    }
+    res.reverse();
+    String::from_utf8(res).unwrap()
+}